@@ -0,0 +1,181 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process,
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+use anyhow::{bail, Context, Result};
+use sha2::{Digest, Sha256};
+
+/// Numbers the temporary files of this process.
+static TMP_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// Content-addressed store of raw pages and downloaded files.
+///
+/// Page contents are kept once under `objects/` by their SHA-256, and every
+/// fetched URL gets a small ref file under `refs/` pointing to its objects.
+#[derive(Debug, Clone)]
+pub struct PageCache {
+    dir: PathBuf,
+}
+
+impl PageCache {
+    pub fn new<P: AsRef<Path>>(dir: P) -> Self {
+        PageCache {
+            dir: dir.as_ref().to_path_buf(),
+        }
+    }
+
+    pub fn get_body(&self, url: &str) -> Result<Option<String>> {
+        match self.read_ref("body", url)?.as_slice() {
+            [] => Ok(None),
+            [hash] => Ok(Some(self.read_text_object(hash)?)),
+            _ => bail!("Malformed cache ref for {url}"),
+        }
+    }
+
+    pub fn put_body(&self, url: &str, body: &str) -> Result<()> {
+        let hash = self.write_object(body.as_bytes())?;
+
+        self.write_ref("body", url, &[hash])
+    }
+
+    pub fn get_bytes(&self, url: &str) -> Result<Option<Vec<u8>>> {
+        match self.read_ref("bytes", url)?.as_slice() {
+            [] => Ok(None),
+            [hash] => Ok(Some(self.read_object(hash)?)),
+            _ => bail!("Malformed cache ref for {url}"),
+        }
+    }
+
+    pub fn put_bytes(&self, url: &str, bytes: &[u8]) -> Result<()> {
+        let hash = self.write_object(bytes)?;
+
+        self.write_ref("bytes", url, &[hash])
+    }
+
+    pub fn get_response(&self, url: &str, payload: &str) -> Result<Option<String>> {
+        match self.read_ref("post", &post_key(url, payload))?.as_slice() {
+            [] => Ok(None),
+            [hash] => Ok(Some(self.read_text_object(hash)?)),
+            _ => bail!("Malformed cache ref for {url}"),
+        }
+    }
+
+    pub fn put_response(&self, url: &str, payload: &str, response: &str) -> Result<()> {
+        let hash = self.write_object(response.as_bytes())?;
+
+        self.write_ref("post", &post_key(url, payload), &[hash])
+    }
+
+    pub fn get_page(&self, url: &str) -> Result<Option<(String, String)>> {
+        match self.read_ref("page", url)?.as_slice() {
+            [] => Ok(None),
+            [head_hash, body_hash] => Ok(Some((
+                self.read_text_object(head_hash)?,
+                self.read_text_object(body_hash)?,
+            ))),
+            _ => bail!("Malformed cache ref for {url}"),
+        }
+    }
+
+    pub fn put_page(&self, url: &str, head: &str, body: &str) -> Result<()> {
+        let head_hash = self.write_object(head.as_bytes())?;
+        let body_hash = self.write_object(body.as_bytes())?;
+
+        self.write_ref("page", url, &[head_hash, body_hash])
+    }
+
+    fn object_path(&self, hash: &str) -> PathBuf {
+        self.dir.join("objects").join(&hash[..2]).join(hash)
+    }
+
+    fn ref_path(&self, kind: &str, url: &str) -> PathBuf {
+        self.dir.join("refs").join(kind).join(hash(url.as_bytes()))
+    }
+
+    fn read_object(&self, hash: &str) -> Result<Vec<u8>> {
+        let path = self.object_path(hash);
+
+        fs::read(&path).context(format!("Failed to read cache object {}", path.display()))
+    }
+
+    fn read_text_object(&self, hash: &str) -> Result<String> {
+        String::from_utf8(self.read_object(hash)?).context("Cache object is not valid UTF-8")
+    }
+
+    fn write_object(&self, content: &[u8]) -> Result<String> {
+        let hash = hash(content);
+        let path = self.object_path(&hash);
+
+        if !path.exists() {
+            write_file(&path, content)?;
+        }
+
+        Ok(hash)
+    }
+
+    /// Ref files hold the URL on the first line and object hashes on the rest.
+    fn read_ref(&self, kind: &str, url: &str) -> Result<Vec<String>> {
+        let path = self.ref_path(kind, url);
+
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+
+        let content = fs::read_to_string(&path)
+            .context(format!("Failed to read cache ref {}", path.display()))?;
+
+        Ok(content.lines().skip(1).map(str::to_string).collect())
+    }
+
+    fn write_ref(&self, kind: &str, url: &str, hashes: &[String]) -> Result<()> {
+        let mut content = url.to_string();
+
+        for hash in hashes {
+            content.push('\n');
+            content.push_str(hash);
+        }
+
+        write_file(&self.ref_path(kind, url), content.as_bytes())
+    }
+}
+
+fn hash(content: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(content))
+}
+
+/// Responses to POST requests are keyed by both the URL and the payload.
+fn post_key(url: &str, payload: &str) -> String {
+    format!("{url} {}", hash(payload.as_bytes()))
+}
+
+fn write_file(path: &Path, content: &[u8]) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .context(format!("Failed to create directory {}", parent.display()))?;
+    }
+
+    // write to a temporary file first so an interrupted run never leaves a
+    // truncated object behind, named uniquely as fetches running at the same
+    // time may write the same object or ref
+    let tmp_path = path.with_extension(format!(
+        "{}.{}.tmp",
+        process::id(),
+        TMP_COUNTER.fetch_add(1, Ordering::Relaxed)
+    ));
+
+    fs::write(&tmp_path, content).context(format!("Failed to write {}", tmp_path.display()))?;
+
+    if let Err(err) = fs::rename(&tmp_path, path) {
+        let _ = fs::remove_file(&tmp_path);
+
+        // another fetch wrote it first, with the same content
+        if !path.exists() {
+            return Err(err).context(format!("Failed to rename to {}", path.display()));
+        }
+    }
+
+    Ok(())
+}
@@ -1,2 +1,10 @@
+pub mod archive;
+pub mod arff;
+pub mod company;
 pub mod entry;
+pub mod error;
+pub mod file_name;
 pub mod flags;
+pub mod relation;
+pub mod subtitle;
+pub mod tag;
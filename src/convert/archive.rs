@@ -0,0 +1,120 @@
+use serde::{Deserialize, Serialize};
+
+use crate::parse::jimaku::file::FileData;
+
+/// A row of the archive member table, one per file inside an opened archive.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchiveRow {
+    pub jimaku_id: i32,
+    pub archive: String,
+    pub member: String,
+    pub size: i64,
+    pub last_modified: String,
+}
+
+#[must_use]
+pub fn get_archive_rows(jimaku_id: i32, files_info: &[FileData]) -> Vec<ArchiveRow> {
+    files_info
+        .iter()
+        .flat_map(|file| {
+            file.members.iter().flatten().map(|member| ArchiveRow {
+                jimaku_id,
+                archive: file.name.clone(),
+                member: member.name.clone(),
+                size: member.size,
+                last_modified: member.last_modified.clone(),
+            })
+        })
+        .collect()
+}
+
+/// The files of an entry with every opened archive replaced by its members,
+/// so a batch counts as the files it holds rather than as one large file.
+/// An archive without files in it stays as it is.
+#[must_use]
+pub fn expand_archives(files_info: &[FileData]) -> Vec<&FileData> {
+    files_info
+        .iter()
+        .flat_map(|file| match &file.members {
+            Some(members) if !members.is_empty() => members.iter().collect(),
+            _ => vec![file],
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::archive::{read_archive, tests::zip, ArchiveFormat};
+
+    fn file(name: &str, size: i64) -> FileData {
+        FileData {
+            name: name.to_string(),
+            size,
+            last_modified: "2024-05-10T21:14:54Z".to_string(),
+            url: None,
+            cues: None,
+            members: None,
+        }
+    }
+
+    /// The archive with its members, as the crawl opens it.
+    fn opened(name: &str, bytes: &[u8]) -> FileData {
+        let members = read_archive(bytes, ArchiveFormat::Zip, |_| false).ok();
+
+        FileData {
+            members: members.map(|members| {
+                members
+                    .into_iter()
+                    .map(|member| file(&member.path, i64::try_from(member.size).unwrap()))
+                    .collect()
+            }),
+            ..file(name, i64::try_from(bytes.len()).unwrap())
+        }
+    }
+
+    #[test]
+    fn lists_the_members_of_opened_archives() {
+        let bytes = zip(&[("01.srt", b"one"), ("02.srt", b"two!")]);
+        let files = [file("03.srt", 5), opened("Show.zip", &bytes)];
+
+        let rows = get_archive_rows(7, &files);
+        let rows: Vec<_> = rows
+            .iter()
+            .map(|row| {
+                (
+                    row.jimaku_id,
+                    row.archive.as_str(),
+                    row.member.as_str(),
+                    row.size,
+                )
+            })
+            .collect();
+        assert_eq!(
+            rows,
+            [(7, "Show.zip", "01.srt", 3), (7, "Show.zip", "02.srt", 4)]
+        );
+
+        let names: Vec<_> = expand_archives(&files)
+            .iter()
+            .map(|file| file.name.as_str())
+            .collect();
+        assert_eq!(names, ["03.srt", "01.srt", "02.srt"]);
+    }
+
+    #[test]
+    fn keeps_archives_that_cannot_be_opened() {
+        let files = [
+            opened("Corrupt.zip", b"PK not really"),
+            opened("Empty.zip", &zip(&[])),
+        ];
+
+        assert!(get_archive_rows(7, &files).is_empty());
+
+        let names: Vec<_> = expand_archives(&files)
+            .iter()
+            .map(|file| file.name.as_str())
+            .collect();
+        assert_eq!(names, ["Corrupt.zip", "Empty.zip"]);
+    }
+}
@@ -0,0 +1,276 @@
+use std::{
+    collections::HashSet,
+    fs::File,
+    io::{BufRead, BufReader, Write},
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Result};
+
+use super::{
+    company::{get_multi_hot_columns, CompanyRow},
+    relation::{get_franchise_columns, RelationRow},
+    tag::{get_weighted_columns, TagRow},
+};
+use crate::storage::{load_rows_from_tsv, relation_path, MISSING_MARKER};
+
+/// How to read the crawled TSV, and the relation tables to turn into extra
+/// attributes.
+#[derive(Debug, Clone)]
+pub struct Options {
+    /// Value the TSV uses for a missing value, written as "?" in the ARFF.
+    /// Crawls write [`MISSING_MARKER`] unless told otherwise
+    pub missing_marker: String,
+    /// Long-format company table written by the crawl
+    pub companies_path: Option<PathBuf>,
+    /// Companies of each role that get their own attribute
+    pub top_companies: usize,
+    /// Long-format tag table written by the crawl
+    pub tags_path: Option<PathBuf>,
+    /// Tags that get their own attribute
+    pub top_tags: usize,
+    /// Relation edge list written by the crawl, to group entries into
+    /// franchises
+    pub relations_path: Option<PathBuf>,
+}
+
+impl Options {
+    /// Uses the relation tables the crawl wrote next to the input, if any.
+    #[must_use]
+    pub fn for_input(input_path: &Path) -> Self {
+        let companies_path = relation_path(input_path, "companies");
+        let tags_path = relation_path(input_path, "tags");
+        let relations_path = relation_path(input_path, "relations");
+
+        Options {
+            missing_marker: MISSING_MARKER.to_string(),
+            companies_path: companies_path.exists().then_some(companies_path),
+            top_companies: 20,
+            tags_path: tags_path.exists().then_some(tags_path),
+            top_tags: 30,
+            relations_path: relations_path.exists().then_some(relations_path),
+        }
+    }
+}
+
+/// A TSV file read as text, so every value is written as it was crawled.
+struct Table {
+    headers: Vec<String>,
+    rows: Vec<Vec<String>>,
+}
+
+impl Table {
+    fn read(path: &Path) -> Result<Self> {
+        let input_file = File::open(path).context(format!("Failed to open {}", path.display()))?;
+        let mut lines = BufReader::new(input_file).lines();
+
+        let headers = match lines.next() {
+            Some(line) => line?
+                .trim_end_matches('\r')
+                .split('\t')
+                .map(str::to_string)
+                .collect(),
+            None => Vec::new(),
+        };
+
+        let rows = lines
+            .map(|line| {
+                Ok(line?
+                    .trim_end_matches('\r')
+                    .split('\t')
+                    .map(str::to_string)
+                    .collect())
+            })
+            .collect::<Result<_>>()?;
+
+        Ok(Table { headers, rows })
+    }
+
+    fn jimaku_ids(&self) -> Result<Vec<i32>> {
+        self.ids("jimaku_id")
+    }
+
+    fn ids(&self, header: &str) -> Result<Vec<i32>> {
+        let index = self
+            .headers
+            .iter()
+            .position(|other| other == header)
+            .context(format!("Input has no {header} column"))?;
+
+        self.rows
+            .iter()
+            .map(|row| {
+                row.get(index)
+                    .context(format!("Row has no {header}"))?
+                    .parse()
+                    .context(format!("Failed to parse {header}"))
+            })
+            .collect()
+    }
+
+    /// Replaces every value equal to the marker with the ARFF missing value.
+    fn mark_missing(&mut self, missing_marker: &str) {
+        for value in self.rows.iter_mut().flatten() {
+            if value == missing_marker {
+                *value = "?".to_string();
+            }
+        }
+    }
+
+    fn push_column(&mut self, header: String, values: Vec<String>) {
+        self.headers.push(header);
+
+        for (row, value) in self.rows.iter_mut().zip(values) {
+            row.push(value);
+        }
+    }
+}
+
+pub fn tsv_to_arff<P: AsRef<Path>, Q: AsRef<Path>>(
+    input_path: P,
+    output_path: Q,
+    options: &Options,
+) -> Result<()> {
+    let mut table = Table::read(input_path.as_ref())?;
+    table.mark_missing(&options.missing_marker);
+
+    if let Some(companies_path) = &options.companies_path {
+        let company_rows: Vec<CompanyRow> = load_rows_from_tsv(companies_path)?;
+        let jimaku_ids = table.jimaku_ids()?;
+
+        for (header, values) in
+            get_multi_hot_columns(&company_rows, &jimaku_ids, options.top_companies)
+        {
+            let values = values.iter().map(bool::to_string).collect();
+            table.push_column(header, values);
+        }
+    }
+
+    if let Some(tags_path) = &options.tags_path {
+        let tag_rows: Vec<TagRow> = load_rows_from_tsv(tags_path)?;
+        let jimaku_ids = table.jimaku_ids()?;
+
+        for (header, values) in get_weighted_columns(&tag_rows, &jimaku_ids, options.top_tags) {
+            let values = values.iter().map(f64::to_string).collect();
+            table.push_column(header, values);
+        }
+    }
+
+    if let Some(relations_path) = &options.relations_path {
+        let relation_rows: Vec<RelationRow> = load_rows_from_tsv(relations_path)?;
+        let anilist_ids = table.ids("anilist_id")?;
+
+        for (header, values) in get_franchise_columns(&relation_rows, &anilist_ids) {
+            let values = values.iter().map(usize::to_string).collect();
+            table.push_column(header, values);
+        }
+    }
+
+    write_arff(&table, output_path.as_ref())
+}
+
+fn write_arff(table: &Table, output_path: &Path) -> Result<()> {
+    let headers = &table.headers;
+    let mut nominal_values: Vec<HashSet<String>> = vec![HashSet::new(); headers.len()];
+
+    for values in &table.rows {
+        for (i, value) in values.iter().enumerate() {
+            if is_skipped(&headers[i]) || value == "?" {
+                continue;
+            }
+            if is_nominal(&headers[i]) {
+                nominal_values[i].insert(value.clone());
+            }
+        }
+    }
+
+    // a nominal attribute needs at least one value, and a column nobody knows
+    // a value of says nothing anyway
+    let is_written: Vec<bool> = headers
+        .iter()
+        .zip(&nominal_values)
+        .map(|(header, values)| !(is_skipped(header) || is_nominal(header) && values.is_empty()))
+        .collect();
+
+    let mut output_file = File::create(output_path)?;
+
+    writeln!(output_file, "@relation data\n")?;
+
+    for (index, header) in headers.iter().enumerate() {
+        if !is_written[index] {
+            continue;
+        }
+
+        let attr_type = if is_nominal(header) {
+            let mut values: Vec<String> = nominal_values[index].iter().cloned().collect();
+            if headers[index].starts_with("company_") {
+                // ids sort by number, names when the crawl looked them up
+                values.sort_by_key(|value| (value.parse::<i32>().ok(), value.clone()));
+            } else {
+                values.sort();
+            }
+            format!(
+                "{{{}}}",
+                values
+                    .iter()
+                    .map(|value| quote_if_needed(value))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )
+        } else if header.starts_with("name_") || header == "genres_unknown" {
+            "string".to_string()
+        } else if header.ends_with("date") {
+            "date 'S'".to_string()
+        } else {
+            "numeric".to_string()
+        };
+        writeln!(output_file, "@attribute {header} {attr_type}")?;
+    }
+
+    writeln!(output_file, "\n@data")?;
+
+    for values in &table.rows {
+        let quoted_values: Vec<String> = values
+            .iter()
+            .enumerate()
+            .filter(|&(index, _)| is_written[index])
+            .map(|(_, value)| quote_if_needed(value))
+            .collect();
+
+        writeln!(output_file, "{}", quoted_values.join(", "))?;
+    }
+
+    Ok(())
+}
+
+fn is_nominal(header: &str) -> bool {
+    header.starts_with("is_")
+        || header == "format"
+        || header == "status"
+        || header == "source"
+        || header == "season"
+        || header == "country"
+        || header.ends_with("_precision")
+        || header.starts_with("company_")
+}
+
+fn is_skipped(header: &str) -> bool {
+    // the ids only identify the row, the crawl bookkeeping says nothing
+    // about the show, and by chance all the shows are not adult so is_adult
+    // is useless
+    matches!(header, "jimaku_id" | "anilist_id" | "removed" | "is_adult")
+}
+
+fn quote_if_needed(value: &str) -> String {
+    if value.is_empty() {
+        "''".to_string()
+    } else if value.contains(' ')
+        || value.contains(',')
+        || value.contains('\'')
+        || value.contains('%')
+    {
+        format!("'{}'", value.replace('\'', "\\\'"))
+    } else {
+        value.to_string()
+    }
+}
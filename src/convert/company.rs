@@ -0,0 +1,155 @@
+use std::collections::{HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+
+use crate::parse::anilist;
+
+/// How a company took part in an anime.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CompanyRole {
+    Production,
+    Producer,
+    Creator,
+}
+
+impl CompanyRole {
+    pub const ALL: [CompanyRole; 3] = [
+        CompanyRole::Production,
+        CompanyRole::Producer,
+        CompanyRole::Creator,
+    ];
+
+    fn name(self) -> &'static str {
+        match self {
+            CompanyRole::Production => "production",
+            CompanyRole::Producer => "producer",
+            CompanyRole::Creator => "creator",
+        }
+    }
+
+    /// Production companies and producers are studios, while creators are
+    /// staff.
+    #[must_use]
+    pub fn kind(self) -> NameKind {
+        match self {
+            CompanyRole::Production | CompanyRole::Producer => NameKind::Studio,
+            CompanyRole::Creator => NameKind::Staff,
+        }
+    }
+}
+
+/// The kind of anilist id a name belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NameKind {
+    Studio,
+    Staff,
+}
+
+/// A row of the studio dimension table.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NameRow {
+    pub kind: NameKind,
+    pub id: i32,
+    pub name: String,
+}
+
+/// Names of the studios and staff, by id.
+#[derive(Debug, Default)]
+pub struct CompanyNames(HashMap<(NameKind, i32), String>);
+
+impl CompanyNames {
+    #[must_use]
+    pub fn new(rows: Vec<NameRow>) -> Self {
+        CompanyNames(
+            rows.into_iter()
+                .map(|row| ((row.kind, row.id), row.name))
+                .collect(),
+        )
+    }
+
+    #[must_use]
+    pub fn get(&self, kind: NameKind, id: i32) -> Option<&str> {
+        self.0.get(&(kind, id)).map(String::as_str)
+    }
+
+    pub fn insert(&mut self, row: NameRow) {
+        self.0.insert((row.kind, row.id), row.name);
+    }
+}
+
+/// A row of the long-format company table, one per company of an entry.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct CompanyRow {
+    pub jimaku_id: i32,
+    pub role: CompanyRole,
+    pub company_id: i32,
+}
+
+/// Lists every company of the anime in the order anilist gives them.
+#[must_use]
+pub fn get_company_rows(jimaku_id: i32, anilist_entry: &anilist::entry::Entry) -> Vec<CompanyRow> {
+    let companies = [
+        (CompanyRole::Production, &anilist_entry.production_companies),
+        (CompanyRole::Producer, &anilist_entry.producers),
+        (CompanyRole::Creator, &anilist_entry.creators),
+    ];
+
+    companies
+        .into_iter()
+        .flat_map(|(role, ids)| {
+            ids.iter().map(move |&company_id| CompanyRow {
+                jimaku_id,
+                role,
+                company_id,
+            })
+        })
+        .collect()
+}
+
+/// Builds a multi-hot column for each of the `top` companies of every role
+/// that appear in the most entries, with a value for each of `jimaku_ids`.
+///
+/// Rows repeated by a resumed crawl are only counted once.
+#[must_use]
+pub fn get_multi_hot_columns(
+    rows: &[CompanyRow],
+    jimaku_ids: &[i32],
+    top: usize,
+) -> Vec<(String, Vec<bool>)> {
+    let rows: HashSet<_> = rows.iter().collect();
+
+    let mut columns = Vec::new();
+
+    for role in CompanyRole::ALL {
+        let mut entry_counts: HashMap<i32, usize> = HashMap::new();
+
+        for row in rows.iter().filter(|row| row.role == role) {
+            *entry_counts.entry(row.company_id).or_default() += 1;
+        }
+
+        // ties go to the smaller id, so the columns do not change between runs
+        let mut companies: Vec<_> = entry_counts.into_iter().collect();
+        companies.sort_by(|(id, count), (other_id, other_count)| {
+            other_count.cmp(count).then(id.cmp(other_id))
+        });
+
+        for (company_id, _) in companies.into_iter().take(top) {
+            let values = jimaku_ids
+                .iter()
+                .map(|&jimaku_id| {
+                    rows.contains(&CompanyRow {
+                        jimaku_id,
+                        role,
+                        company_id,
+                    })
+                })
+                .collect();
+
+            columns.push((format!("is_company_{}_{company_id}", role.name()), values));
+        }
+    }
+
+    columns
+}
@@ -1,18 +1,31 @@
 use crate::parse::{
-    anilist::{self, genre::Genre},
+    anilist::{self, date::PartialDate, genre::Genre, relation::RelationType},
     jimaku::{self, file::FileData},
 };
-use anyhow::{bail, Context, Result};
-use chrono::{DateTime, NaiveDate, Utc};
+use anyhow::Result;
+use chrono::{DateTime, NaiveTime};
 use serde::Serialize;
 
-use super::flags::EntryFlags;
+use super::{
+    archive::expand_archives,
+    company::{CompanyNames, CompanyRole},
+    error::ConvertError,
+    file_name::calculate_file_name_stats,
+    flags::EntryFlags,
+    subtitle::calculate_subtitle_stats,
+};
 
-#[derive(Debug, Serialize)]
+/// A row of the dataset. Values that are not known are `None`, which is
+/// written as the missing marker of the crawl.
+#[derive(Debug, Default, Serialize)]
 pub struct TsvEntry {
+    pub jimaku_id: i32,
+    /// The entry was gone from the listing of the last incremental crawl
+    pub removed: bool,
+    pub anilist_id: i32,
     pub name_romaji: String,
-    pub name_english: String,
-    pub name_japanese: String,
+    pub name_english: Option<String>,
+    pub name_japanese: Option<String>,
     pub is_unverified: bool,
     pub is_external: bool,
     pub is_movie: bool,
@@ -36,86 +49,187 @@ pub struct TsvEntry {
     pub is_sports: bool,
     pub is_supernatural: bool,
     pub is_thriller: bool,
+    /// Genres outside the fixed list, separated by "|", empty when there are
+    /// none
+    pub genres_unknown: String,
 
     pub format: String,
     pub status: String,
-    pub source: String,
-    pub episodes_amount: i32,
-    pub time_required: i64,
-    pub start_date: i64,
-    pub end_date: i64,
-    pub rating_value: i32,
-    pub rating_count: i32,
-    pub company_production: i32,
-    pub company_producer: i32,
-    pub company_creator: i32,
+    pub source: Option<String>,
+    pub episodes_amount: Option<i32>,
+    pub time_required: Option<i64>,
+    /// Epoch of the first day the anime can start
+    pub start_date: Option<i64>,
+    /// How much of the start date is known: year, month or day
+    pub start_date_precision: Option<String>,
+    pub end_date: Option<i64>,
+    pub end_date_precision: Option<String>,
+    pub season: Option<String>,
+    pub season_year: Option<i32>,
+    pub country: Option<String>,
+    pub is_licensed: Option<bool>,
+    pub rating_value: Option<i32>,
+    pub rating_count: Option<i32>,
+    pub popularity: Option<i32>,
+    pub favourites: Option<i32>,
+    pub mean_score: Option<i32>,
+    /// All-time ranks, `None` when unranked
+    pub rank_rated: Option<i32>,
+    pub rank_popular: Option<i32>,
+    pub score_10: Option<i32>,
+    pub score_20: Option<i32>,
+    pub score_30: Option<i32>,
+    pub score_40: Option<i32>,
+    pub score_50: Option<i32>,
+    pub score_60: Option<i32>,
+    pub score_70: Option<i32>,
+    pub score_80: Option<i32>,
+    pub score_90: Option<i32>,
+    pub score_100: Option<i32>,
+    pub status_current: Option<i32>,
+    pub status_planning: Option<i32>,
+    pub status_completed: Option<i32>,
+    pub status_dropped: Option<i32>,
+    pub status_paused: Option<i32>,
+    pub status_repeating: Option<i32>,
+    pub company_production: Option<String>,
+    pub company_producer: Option<String>,
+    pub company_creator: Option<String>,
+    pub production_companies_count: usize,
+    pub producers_count: usize,
+    pub creators_count: usize,
+    pub tags_count: usize,
+    pub relations_count: usize,
+    /// Whether the anime has a prequel
+    pub is_sequel: bool,
+    /// Whether the anime has a sequel
+    pub is_prequel: bool,
 
+    /// When the jimaku entry last changed, which an incremental crawl
+    /// compares against the listing
     pub last_modified: i64,
-    pub file_modified_first: i64,
-    pub file_modified_last: i64,
-    pub file_modified_median: i64,
-    pub filesize_min: i64,
-    pub filesize_max: i64,
-    pub filesize_median: i64,
+    pub file_modified_first: Option<i64>,
+    pub file_modified_last: Option<i64>,
+    pub file_modified_median: Option<i64>,
+    pub filesize_min: Option<i64>,
+    pub filesize_max: Option<i64>,
+    pub filesize_median: Option<i64>,
+    pub files_count: usize,
+    pub files_srt: usize,
+    pub files_ass: usize,
+    pub files_vtt: usize,
+    pub files_archive: usize,
+    pub archive_members: usize,
+    pub files_other: usize,
+    pub files_japanese: usize,
+    pub files_english: usize,
+    pub groups_count: usize,
+    pub resolution_max: Option<u32>,
+    pub episodes_covered: usize,
+    pub episode_coverage: Option<f64>,
+    /// Filled when the subtitles were downloaded
+    pub subtitles_downloaded: Option<usize>,
+    pub cues_count: Option<usize>,
+    pub spoken_duration: Option<i64>,
+    pub chars_per_minute: Option<f64>,
+    pub kanji_ratio: Option<f64>,
+    pub distinct_tokens: Option<usize>,
 }
 
+#[allow(clippy::too_many_lines)]
 pub fn get_tsv_entry(
     jimaku_entry: &jimaku::entry::Entry,
     jimaku_files_info: &[jimaku::file::FileData],
     anilist_entry: &anilist::entry::Entry,
+    company_names: Option<&CompanyNames>,
 ) -> Result<TsvEntry> {
-    let file_stats = calculate_file_stats(jimaku_files_info);
+    let files = expand_archives(jimaku_files_info);
+    let file_stats = calculate_file_stats(&files);
+    let file_name_stats =
+        calculate_file_name_stats(jimaku_files_info, anilist_entry.episodes_amount);
+    let subtitle_stats = calculate_subtitle_stats(&files);
+    let scores = anilist_entry.score_distribution.as_ref();
+    let statuses = anilist_entry.status_distribution.as_ref();
 
     Ok(TsvEntry {
+        jimaku_id: jimaku_entry.id,
+        removed: false,
+        anilist_id: jimaku_entry.anilist_id.unwrap_or_default(),
         name_romaji: jimaku_entry.name.clone(),
-        name_english: match &jimaku_entry.english_name {
-            Some(name) => name.to_string(),
-            None => "?".to_string(),
-        },
-        name_japanese: match &jimaku_entry.japanese_name {
-            Some(name) => name.to_string(),
-            None => "?".to_string(),
-        },
+        name_english: jimaku_entry.english_name.clone(),
+        name_japanese: jimaku_entry.japanese_name.clone(),
         is_unverified: EntryFlags::new(jimaku_entry.flags).is_unverified(),
         is_external: EntryFlags::new(jimaku_entry.flags).is_external(),
         is_movie: EntryFlags::new(jimaku_entry.flags).is_movie(),
         is_adult: EntryFlags::new(jimaku_entry.flags).is_adult(),
         format: anilist_entry.format.to_string(),
         status: anilist_entry.status.to_string(),
-        source: match &anilist_entry.source {
-            Some(source) => source.to_string(),
-            None => "?".to_string(),
-        },
-        episodes_amount: match anilist_entry.episodes_amount {
-            Some(episodes_amount) => episodes_amount,
-            None => 0,
-        },
-        time_required: match &anilist_entry.time_required {
-            Some(time) => parse_time(time)?,
-            None => 0,
-        },
-        start_date: match &anilist_entry.start_date {
-            Some(start_date) => parse_date(start_date)?,
-            None => 0,
-        },
-        end_date: match &anilist_entry.end_date {
-            Some(end_date) => parse_date(end_date)?,
-            None => 0,
-        },
+        source: anilist_entry.source.as_ref().map(ToString::to_string),
+        episodes_amount: anilist_entry.episodes_amount,
+        time_required: anilist_entry
+            .time_required
+            .as_deref()
+            .map(parse_time)
+            .transpose()?,
+        start_date: anilist_entry.start_date.map(date_value),
+        start_date_precision: anilist_entry
+            .start_date
+            .as_ref()
+            .map(|date| date.precision().to_string()),
+        end_date: anilist_entry.end_date.map(date_value),
+        end_date_precision: anilist_entry
+            .end_date
+            .as_ref()
+            .map(|date| date.precision().to_string()),
+        season: anilist_entry.season.as_ref().map(ToString::to_string),
+        season_year: anilist_entry.season_year,
+        country: anilist_entry.country_of_origin.clone(),
+        is_licensed: anilist_entry.is_licensed,
         rating_value: anilist_entry.rating_value,
         rating_count: anilist_entry.rating_count,
-        company_production: match anilist_entry.production_company {
-            Some(company) => company,
-            None => 0,
-        },
-        company_producer: match anilist_entry.producer {
-            Some(company) => company,
-            None => 0,
-        },
-        company_creator: match anilist_entry.creator {
-            Some(company) => company,
-            None => 0,
-        },
+        popularity: anilist_entry.popularity,
+        favourites: anilist_entry.favourites,
+        mean_score: anilist_entry.mean_score,
+        rank_rated: anilist_entry.rankings.rated,
+        rank_popular: anilist_entry.rankings.popular,
+        score_10: scores.map(|scores| scores.get(10)),
+        score_20: scores.map(|scores| scores.get(20)),
+        score_30: scores.map(|scores| scores.get(30)),
+        score_40: scores.map(|scores| scores.get(40)),
+        score_50: scores.map(|scores| scores.get(50)),
+        score_60: scores.map(|scores| scores.get(60)),
+        score_70: scores.map(|scores| scores.get(70)),
+        score_80: scores.map(|scores| scores.get(80)),
+        score_90: scores.map(|scores| scores.get(90)),
+        score_100: scores.map(|scores| scores.get(100)),
+        status_current: statuses.map(|statuses| statuses.current),
+        status_planning: statuses.map(|statuses| statuses.planning),
+        status_completed: statuses.map(|statuses| statuses.completed),
+        status_dropped: statuses.map(|statuses| statuses.dropped),
+        status_paused: statuses.map(|statuses| statuses.paused),
+        status_repeating: statuses.map(|statuses| statuses.repeating),
+        company_production: company_value(
+            &anilist_entry.production_companies,
+            CompanyRole::Production,
+            company_names,
+        ),
+        company_producer: company_value(
+            &anilist_entry.producers,
+            CompanyRole::Producer,
+            company_names,
+        ),
+        company_creator: company_value(
+            &anilist_entry.creators,
+            CompanyRole::Creator,
+            company_names,
+        ),
+        production_companies_count: anilist_entry.production_companies.len(),
+        producers_count: anilist_entry.producers.len(),
+        creators_count: anilist_entry.creators.len(),
+        tags_count: anilist_entry.tags.len(),
+        relations_count: anilist_entry.relations.len(),
+        is_sequel: has_relation(anilist_entry, &RelationType::Prequel),
+        is_prequel: has_relation(anilist_entry, &RelationType::Sequel),
         last_modified: jimaku_entry.last_modified,
         file_modified_first: file_stats.file_modified_first,
         file_modified_last: file_stats.file_modified_last,
@@ -123,6 +237,29 @@ pub fn get_tsv_entry(
         filesize_min: file_stats.filesize_min,
         filesize_max: file_stats.filesize_max,
         filesize_median: file_stats.filesize_median,
+        files_count: file_name_stats.files_count,
+        files_srt: file_name_stats.files_srt,
+        files_ass: file_name_stats.files_ass,
+        files_vtt: file_name_stats.files_vtt,
+        files_archive: file_name_stats.files_archive,
+        archive_members: file_name_stats.archive_members,
+        files_other: file_name_stats.files_other,
+        files_japanese: file_name_stats.files_japanese,
+        files_english: file_name_stats.files_english,
+        groups_count: file_name_stats.groups_count,
+        resolution_max: file_name_stats.resolution_max,
+        episodes_covered: file_name_stats.episodes_covered,
+        episode_coverage: file_name_stats.episode_coverage,
+        subtitles_downloaded: subtitle_stats
+            .as_ref()
+            .map(|stats| stats.subtitles_downloaded),
+        cues_count: subtitle_stats.as_ref().map(|stats| stats.cues_count),
+        spoken_duration: subtitle_stats.as_ref().map(|stats| stats.spoken_duration),
+        chars_per_minute: subtitle_stats
+            .as_ref()
+            .and_then(|stats| stats.chars_per_minute),
+        kanji_ratio: subtitle_stats.as_ref().and_then(|stats| stats.kanji_ratio),
+        distinct_tokens: subtitle_stats.as_ref().map(|stats| stats.distinct_tokens),
         is_action: anilist_entry.genres.contains(&Genre::Action),
         is_adventure: anilist_entry.genres.contains(&Genre::Adventure),
         is_comedy: anilist_entry.genres.contains(&Genre::Comedy),
@@ -141,26 +278,38 @@ pub fn get_tsv_entry(
         is_sports: anilist_entry.genres.contains(&Genre::Sports),
         is_supernatural: anilist_entry.genres.contains(&Genre::Supernatural),
         is_thriller: anilist_entry.genres.contains(&Genre::Thriller),
+        genres_unknown: anilist_entry
+            .genres
+            .iter()
+            .filter_map(|genre| match genre {
+                Genre::Unknown(genre) => Some(genre.as_str()),
+                _ => None,
+            })
+            .collect::<Vec<_>>()
+            .join("|"),
     })
 }
 
+/// Statistics of the files of an entry, `None` when it has no files, or no
+/// file with a readable date for the date columns.
 #[derive(Debug)]
 struct FileStats {
-    file_modified_first: i64,
-    file_modified_last: i64,
-    file_modified_median: i64,
-    filesize_min: i64,
-    filesize_max: i64,
-    filesize_median: i64,
+    file_modified_first: Option<i64>,
+    file_modified_last: Option<i64>,
+    file_modified_median: Option<i64>,
+    filesize_min: Option<i64>,
+    filesize_max: Option<i64>,
+    filesize_median: Option<i64>,
 }
 
-fn calculate_file_stats(files_info: &[FileData]) -> FileStats {
+fn calculate_file_stats(files_info: &[&FileData]) -> FileStats {
+    // a file whose date cannot be read is left out of the date columns
     let mut modified_times: Vec<i64> = files_info
         .iter()
-        .map(|file| {
+        .filter_map(|file| {
             DateTime::parse_from_rfc3339(&file.last_modified)
                 .map(|dt| dt.timestamp())
-                .unwrap_or(0)
+                .ok()
         })
         .collect();
     let mut file_sizes: Vec<i64> = files_info.iter().map(|file| file.size).collect();
@@ -168,53 +317,67 @@ fn calculate_file_stats(files_info: &[FileData]) -> FileStats {
     modified_times.sort_unstable();
     file_sizes.sort_unstable();
 
-    let file_modified_first = *modified_times.first().unwrap_or(&0);
-    let file_modified_last = *modified_times.last().unwrap_or(&0);
-    let file_modified_median = if modified_times.len() % 2 == 0 {
-        (modified_times[modified_times.len() / 2 - 1] + modified_times[modified_times.len() / 2])
-            / 2
-    } else {
-        modified_times[modified_times.len() / 2]
-    };
+    FileStats {
+        file_modified_first: modified_times.first().copied(),
+        file_modified_last: modified_times.last().copied(),
+        file_modified_median: median(&modified_times),
+        filesize_min: file_sizes.first().copied(),
+        filesize_max: file_sizes.last().copied(),
+        filesize_median: median(&file_sizes),
+    }
+}
 
-    let filesize_min = *file_sizes.first().unwrap_or(&0);
-    let filesize_max = *file_sizes.last().unwrap_or(&0);
-    let filesize_median = if file_sizes.len() % 2 == 0 {
-        (file_sizes[file_sizes.len() / 2 - 1] + file_sizes[file_sizes.len() / 2]) / 2
-    } else {
-        file_sizes[file_sizes.len() / 2]
-    };
+/// Median of sorted values, `None` when there are none.
+fn median(sorted: &[i64]) -> Option<i64> {
+    let middle = sorted.len() / 2;
 
-    FileStats {
-        file_modified_first,
-        file_modified_last,
-        file_modified_median,
-        filesize_min,
-        filesize_max,
-        filesize_median,
+    if sorted.len().is_multiple_of(2) {
+        let low = *sorted.get(middle.checked_sub(1)?)?;
+        let high = sorted[middle];
+
+        Some(low + (high - low) / 2)
+    } else {
+        Some(sorted[middle])
     }
 }
 
-fn parse_time(time: &str) -> Result<i64> {
-    let duration: iso8601_duration::Duration = match time.parse() {
-        Ok(duration) => duration,
-        Err(err) => bail!(format!("Failed to parse ISO8601 duration: {err:?}")),
-    };
+fn has_relation(anilist_entry: &anilist::entry::Entry, relation_type: &RelationType) -> bool {
+    anilist_entry
+        .relations
+        .iter()
+        .any(|relation| relation.relation_type == *relation_type)
+}
+
+/// The first company of the role, by name when names are given and known,
+/// otherwise by id.
+fn company_value(
+    ids: &[i32],
+    role: CompanyRole,
+    company_names: Option<&CompanyNames>,
+) -> Option<String> {
+    let &id = ids.first()?;
+
+    let value = company_names
+        .and_then(|names| names.get(role.kind(), id))
+        .map_or_else(|| id.to_string(), str::to_string);
+
+    Some(value)
+}
+
+fn parse_time(time: &str) -> Result<i64, ConvertError> {
+    let duration: iso8601_duration::Duration = time
+        .parse()
+        .map_err(|_| ConvertError::InvalidDuration(time.to_string()))?;
 
     let duration = duration
         .to_chrono()
-        .context("Failed to convert duration to chrono")?;
+        .ok_or_else(|| ConvertError::InvalidDuration(time.to_string()))?;
     Ok(duration.num_milliseconds())
 }
 
-fn parse_date(date: &str) -> Result<i64> {
-    let naive_date = NaiveDate::parse_from_str(date, "%Y-%m-%d")
-        .context(format!("Failed to parse naive date from string {date}"))?;
-    let datetime: DateTime<Utc> = DateTime::from_naive_utc_and_offset(
-        naive_date
-            .and_hms_opt(0, 0, 0)
-            .context("Failed to make NaiveDateTime")?,
-        Utc,
-    );
-    Ok(datetime.timestamp())
+fn date_value(date: PartialDate) -> i64 {
+    date.first_day()
+        .and_time(NaiveTime::MIN)
+        .and_utc()
+        .timestamp()
 }
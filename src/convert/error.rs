@@ -0,0 +1,20 @@
+use thiserror::Error;
+
+/// Why a crawled value could not be converted into a dataset column.
+#[derive(Debug, Error)]
+pub enum ConvertError {
+    #[error("Invalid ISO8601 duration: {0}")]
+    InvalidDuration(String),
+}
+
+impl ConvertError {
+    /// Short name of the kind of failure, for the failure summary. The value
+    /// stays the same however often it is converted, so there is no
+    /// transient kind.
+    #[must_use]
+    pub fn category(&self) -> &'static str {
+        match self {
+            ConvertError::InvalidDuration(_) => "invalid duration",
+        }
+    }
+}
@@ -0,0 +1,105 @@
+use std::collections::{BTreeSet, HashSet};
+
+use crate::parse::jimaku::{
+    file::FileData,
+    file_name::{parse_file_name, Language},
+};
+
+use super::archive::expand_archives;
+
+/// What the file names of an entry tell together.
+#[derive(Debug, Default)]
+pub struct FileNameStats {
+    pub files_count: usize,
+    pub files_srt: usize,
+    pub files_ass: usize,
+    pub files_vtt: usize,
+    /// Zip, 7z and rar files, whether they were opened or not
+    pub files_archive: usize,
+    /// Files inside the opened archives
+    pub archive_members: usize,
+    pub files_other: usize,
+    pub files_japanese: usize,
+    pub files_english: usize,
+    pub groups_count: usize,
+    pub resolution_max: Option<u32>,
+    pub episodes_covered: usize,
+    /// Share of the episodes of the anime that some file covers
+    pub episode_coverage: Option<f64>,
+}
+
+/// Files of the opened archives count in place of the archives, which only
+/// count toward `files_archive`.
+#[must_use]
+pub fn calculate_file_name_stats(
+    files_info: &[FileData],
+    episodes_amount: Option<i32>,
+) -> FileNameStats {
+    let files = expand_archives(files_info);
+
+    let mut stats = FileNameStats {
+        files_count: files.len(),
+        files_archive: files_info
+            .iter()
+            .filter(|file| is_archive(parse_file_name(&file.name).extension.as_deref()))
+            .count(),
+        archive_members: files_info
+            .iter()
+            .filter_map(|file| file.members.as_ref())
+            .map(Vec::len)
+            .sum(),
+        ..FileNameStats::default()
+    };
+
+    let mut groups = HashSet::new();
+    let mut episodes = BTreeSet::new();
+
+    for file in files {
+        let file_name = parse_file_name(&file.name);
+        let extension = file_name.extension.as_deref();
+
+        let count = match extension {
+            Some("srt") => Some(&mut stats.files_srt),
+            Some("ass" | "ssa") => Some(&mut stats.files_ass),
+            Some("vtt") => Some(&mut stats.files_vtt),
+            _ if is_archive(extension) => None,
+            _ => Some(&mut stats.files_other),
+        };
+        if let Some(count) = count {
+            *count += 1;
+        }
+
+        match file_name.language {
+            Some(Language::Japanese) => stats.files_japanese += 1,
+            Some(Language::English) => stats.files_english += 1,
+            None => {}
+        }
+
+        if let Some(group) = file_name.group {
+            groups.insert(group.to_lowercase());
+        }
+
+        stats.resolution_max = stats.resolution_max.max(file_name.resolution);
+
+        if let Some(range) = file_name.episodes {
+            episodes.extend(range.first..=range.last);
+        }
+    }
+
+    stats.groups_count = groups.len();
+    stats.episodes_covered = episodes.len();
+    stats.episode_coverage = episodes_amount
+        .and_then(|amount| u32::try_from(amount).ok())
+        .filter(|&amount| amount > 0)
+        .map(|amount| {
+            // at most `amount`, so it always fits
+            let covered = u32::try_from(episodes.range(1..=amount).count()).unwrap_or(amount);
+            f64::from(covered) / f64::from(amount)
+        });
+
+    stats
+}
+
+fn is_archive(extension: Option<&str>) -> bool {
+    matches!(extension, Some("zip" | "7z" | "rar"))
+}
@@ -0,0 +1,121 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::parse::anilist::{self, relation::to_relation_type};
+
+/// A row of the relation edge list, one per relation of an entry.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RelationRow {
+    pub jimaku_id: i32,
+    pub anilist_id: i32,
+    pub relation: String,
+    pub target_id: i32,
+    pub target_type: String,
+}
+
+#[must_use]
+pub fn get_relation_rows(
+    jimaku_id: i32,
+    anilist_id: i32,
+    anilist_entry: &anilist::entry::Entry,
+) -> Vec<RelationRow> {
+    anilist_entry
+        .relations
+        .iter()
+        .map(|relation| RelationRow {
+            jimaku_id,
+            anilist_id,
+            relation: relation.relation_type.to_string(),
+            target_id: relation.target_id,
+            target_type: relation.target_type.clone(),
+        })
+        .collect()
+}
+
+/// Groups the anime linked by franchise relations, and builds a column with
+/// the size of the franchise of each of `anilist_ids` and one with how many
+/// of them share it.
+///
+/// Only relations of the crawled entries are known, so a franchise is only
+/// joined through anime that have a jimaku entry.
+#[must_use]
+pub fn get_franchise_columns(
+    rows: &[RelationRow],
+    anilist_ids: &[i32],
+) -> Vec<(String, Vec<usize>)> {
+    let mut franchises = Franchises::default();
+
+    for &anilist_id in anilist_ids {
+        franchises.find(anilist_id);
+    }
+
+    for row in rows {
+        if row.target_type == "anime" && to_relation_type(&row.relation).is_franchise() {
+            franchises.join(row.anilist_id, row.target_id);
+        }
+    }
+
+    let mut sizes: HashMap<i32, usize> = HashMap::new();
+    let mut anime = franchises.parents.keys().copied().collect::<Vec<_>>();
+    anime.sort_unstable();
+
+    for id in anime {
+        *sizes.entry(franchises.find(id)).or_default() += 1;
+    }
+
+    let mut entries: HashMap<i32, usize> = HashMap::new();
+
+    for &anilist_id in anilist_ids {
+        *entries.entry(franchises.find(anilist_id)).or_default() += 1;
+    }
+
+    let roots: Vec<_> = anilist_ids
+        .iter()
+        .map(|&anilist_id| franchises.find(anilist_id))
+        .collect();
+
+    vec![
+        (
+            "franchise_size".to_string(),
+            roots.iter().map(|root| sizes[root]).collect(),
+        ),
+        (
+            "franchise_entries".to_string(),
+            roots.iter().map(|root| entries[root]).collect(),
+        ),
+    ]
+}
+
+/// Disjoint sets of anilist ids.
+#[derive(Debug, Default)]
+struct Franchises {
+    parents: HashMap<i32, i32>,
+}
+
+impl Franchises {
+    fn find(&mut self, id: i32) -> i32 {
+        let parent = *self.parents.entry(id).or_insert(id);
+
+        if parent == id {
+            return id;
+        }
+
+        let root = self.find(parent);
+        self.parents.insert(id, root);
+        root
+    }
+
+    fn join(&mut self, id: i32, other_id: i32) {
+        let root = self.find(id);
+        let other_root = self.find(other_id);
+
+        // the smaller id becomes the root, so the result does not depend on
+        // the order of the rows
+        if root < other_root {
+            self.parents.insert(other_root, root);
+        } else {
+            self.parents.insert(root, other_root);
+        }
+    }
+}
@@ -0,0 +1,138 @@
+use std::collections::HashSet;
+
+use crate::parse::{jimaku::file::FileData, subtitle::cue::Cue};
+
+/// What the downloaded subtitles of an entry hold together.
+#[derive(Debug, Default)]
+pub struct SubtitleStats {
+    pub subtitles_downloaded: usize,
+    pub cues_count: usize,
+    /// Milliseconds where some cue is shown, counted once per file
+    pub spoken_duration: i64,
+    pub chars_per_minute: Option<f64>,
+    /// Kanji among the kanji and kana, which is higher for harder text
+    pub kanji_ratio: Option<f64>,
+    pub distinct_tokens: usize,
+}
+
+/// Script of a character, used to split text without spaces into tokens.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Script {
+    Kanji,
+    Hiragana,
+    Katakana,
+    Latin,
+    Other,
+}
+
+/// Sums up the cues of the files that were downloaded, or returns `None`
+/// when none were.
+#[must_use]
+pub fn calculate_subtitle_stats(files_info: &[&FileData]) -> Option<SubtitleStats> {
+    let downloaded: Vec<&[Cue]> = files_info
+        .iter()
+        .filter_map(|file| file.cues.as_deref())
+        .collect();
+
+    if downloaded.is_empty() {
+        return None;
+    }
+
+    let mut stats = SubtitleStats {
+        subtitles_downloaded: downloaded.len(),
+        ..SubtitleStats::default()
+    };
+
+    let mut chars: u32 = 0;
+    let mut kanji: u32 = 0;
+    let mut kana: u32 = 0;
+    let mut tokens = HashSet::new();
+
+    for cues in downloaded {
+        stats.cues_count += cues.len();
+        stats.spoken_duration = stats.spoken_duration.saturating_add(spoken_duration(cues));
+
+        for cue in cues {
+            for letter in cue.text.chars().filter(|letter| !letter.is_whitespace()) {
+                chars += 1;
+
+                match script(letter) {
+                    Script::Kanji => kanji += 1,
+                    Script::Hiragana | Script::Katakana => kana += 1,
+                    Script::Latin | Script::Other => {}
+                }
+            }
+
+            tokens.extend(tokenize(&cue.text));
+        }
+    }
+
+    stats.distinct_tokens = tokens.len();
+
+    #[allow(clippy::cast_precision_loss)]
+    let minutes = stats.spoken_duration as f64 / 60_000.0;
+    stats.chars_per_minute = (minutes > 0.0).then(|| f64::from(chars) / minutes);
+
+    stats.kanji_ratio = (kanji + kana > 0).then(|| f64::from(kanji) / f64::from(kanji + kana));
+
+    Some(stats)
+}
+
+/// Time covered by the cues, where overlapping cues count once.
+fn spoken_duration(cues: &[Cue]) -> i64 {
+    let mut spans: Vec<_> = cues.iter().map(|cue| (cue.start, cue.end)).collect();
+    spans.sort_unstable();
+
+    let mut duration = 0;
+    let mut covered_until = i64::MIN;
+
+    for (start, end) in spans {
+        let start = start.max(covered_until);
+
+        if end > start {
+            duration += end - start;
+            covered_until = end;
+        }
+    }
+
+    duration
+}
+
+/// Splits the text where the script changes, so "俺は走る" gives "俺", "は",
+/// "走" and "る". Without a dictionary this only roughly follows words, but
+/// it is enough to compare how varied the vocabulary is.
+fn tokenize(text: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut token = String::new();
+    let mut token_script = Script::Other;
+
+    for letter in text.chars() {
+        let letter_script = script(letter);
+
+        if letter_script != token_script && !token.is_empty() {
+            tokens.push(std::mem::take(&mut token));
+        }
+
+        if letter_script != Script::Other {
+            token.extend(letter.to_lowercase());
+        }
+
+        token_script = letter_script;
+    }
+
+    if !token.is_empty() {
+        tokens.push(token);
+    }
+
+    tokens
+}
+
+fn script(letter: char) -> Script {
+    match letter {
+        '\u{4e00}'..='\u{9fff}' | '\u{3400}'..='\u{4dbf}' | '々' => Script::Kanji,
+        '\u{3040}'..='\u{309f}' => Script::Hiragana,
+        '\u{30a0}'..='\u{30ff}' | '\u{ff66}'..='\u{ff9f}' => Script::Katakana,
+        _ if letter.is_alphanumeric() => Script::Latin,
+        _ => Script::Other,
+    }
+}
@@ -0,0 +1,86 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::parse::anilist;
+
+/// A row of the long-format tag table, one per tag of an entry.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TagRow {
+    pub jimaku_id: i32,
+    pub name: String,
+    pub category: Option<String>,
+    pub rank: i32,
+}
+
+#[must_use]
+pub fn get_tag_rows(jimaku_id: i32, anilist_entry: &anilist::entry::Entry) -> Vec<TagRow> {
+    anilist_entry
+        .tags
+        .iter()
+        .map(|tag| TagRow {
+            jimaku_id,
+            name: tag.name.clone(),
+            category: tag.category.clone(),
+            rank: tag.rank,
+        })
+        .collect()
+}
+
+/// Builds a column for each of the `top` tags found in the most entries,
+/// holding the rank of the tag as a weight from 0 to 1 for each of
+/// `jimaku_ids`, and 0 where the tag is missing.
+///
+/// Rows repeated by a resumed crawl only count once.
+#[must_use]
+pub fn get_weighted_columns(
+    rows: &[TagRow],
+    jimaku_ids: &[i32],
+    top: usize,
+) -> Vec<(String, Vec<f64>)> {
+    let ranks: HashMap<(&str, i32), i32> = rows
+        .iter()
+        .map(|row| ((row.name.as_str(), row.jimaku_id), row.rank))
+        .collect();
+
+    let mut entry_counts: HashMap<&str, usize> = HashMap::new();
+
+    for &(name, _) in ranks.keys() {
+        *entry_counts.entry(name).or_default() += 1;
+    }
+
+    // ties go to the name, so the columns do not change between runs
+    let mut tags: Vec<_> = entry_counts.into_iter().collect();
+    tags.sort_by(|(name, count), (other_name, other_count)| {
+        other_count.cmp(count).then(name.cmp(other_name))
+    });
+
+    tags.into_iter()
+        .take(top)
+        .map(|(name, _)| {
+            let values = jimaku_ids
+                .iter()
+                .map(|&jimaku_id| {
+                    ranks
+                        .get(&(name, jimaku_id))
+                        .map_or(0.0, |&rank| f64::from(rank) / 100.0)
+                })
+                .collect();
+
+            (format!("tag_{}", to_attribute_name(name)), values)
+        })
+        .collect()
+}
+
+/// Turns "Time Skip" into `time_skip`.
+fn to_attribute_name(name: &str) -> String {
+    name.chars()
+        .map(|letter| {
+            if letter.is_alphanumeric() {
+                letter.to_ascii_lowercase()
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,834 @@
+use std::{
+    collections::{HashMap, HashSet},
+    path::PathBuf,
+    sync::Arc,
+    time::Duration,
+};
+
+use anyhow::{bail, Context, Result};
+use futures::{future::try_join_all, stream, StreamExt};
+
+use crate::{
+    cache::PageCache,
+    convert::{
+        archive::{get_archive_rows, ArchiveRow},
+        company::{get_company_rows, CompanyRow},
+        entry::{get_tsv_entry, TsvEntry},
+        relation::{get_relation_rows, RelationRow},
+        tag::{get_tag_rows, TagRow},
+    },
+    fetch::{
+        cached::CachedFetcher, chrome::ChromeFetcher, combined::CombinedFetcher, file::FileFetcher,
+        http::HttpFetcher, Fetcher,
+    },
+    parse::{
+        anilist::{self, api, entry::parse_anilist_entry},
+        archive::{read_archive, ArchiveFormat, ArchiveMember},
+        jimaku::{
+            self,
+            entry::parse_entries,
+            file::{parse_files_data, FileData},
+            file_name::parse_file_name,
+        },
+        subtitle::format::{parse_subtitle, SubtitleFormat},
+    },
+    request::{rate_limit::RateLimiter, RetryPolicy},
+    storage::{
+        check_saved_header, load_saved_ids, relation_path, remove_file_if_exists,
+        remove_saved_rows, replace_saved_entries, save_rows_to_tsv, save_to_tsv,
+    },
+};
+
+use self::{failure::Failure, names::NameLookup, refresh::Refresh, report::Report};
+
+pub mod failure;
+pub mod names;
+pub mod refresh;
+pub mod report;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    /// Skip entries already saved to the output
+    Resume,
+    /// Discard the output and crawl every entry again
+    Restart,
+    /// Crawl saved entries again when their listing changed, and mark the
+    /// ones no longer listed as removed when the listing is complete
+    Incremental,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JimakuSource {
+    /// Scrape the listing and entry pages
+    Page,
+    /// Query the JSON API, which needs an API key
+    Api,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AnilistSource {
+    /// Render the anime page in the browser and scrape it
+    Page,
+    /// Query the GraphQL API
+    Api,
+}
+
+#[derive(Debug, Clone)]
+#[allow(clippy::struct_excessive_bools)]
+pub struct Config {
+    pub listing_urls: Vec<String>,
+    /// The listing urls list every jimaku entry, so an incremental crawl can
+    /// mark the saved entries missing from them as removed
+    pub complete_listing: bool,
+    pub jimaku_url: String,
+    pub jimaku_source: JimakuSource,
+    pub jimaku_api_key: Option<String>,
+    pub anilist_url: String,
+    pub anilist_api_url: String,
+    pub anilist_source: AnilistSource,
+    /// Anilist ids to query in one API request
+    pub anilist_batch_size: usize,
+    /// Requests for different entries that may run at the same time
+    pub concurrency: usize,
+    pub output_path: PathBuf,
+    /// Written to the output for values that are not known
+    pub missing_marker: String,
+    pub mode: Mode,
+    pub max_entries: Option<usize>,
+    /// Transient failures in a row after which the crawl is aborted
+    pub max_failures_in_a_row: u32,
+    /// Rounds of trying again the entries that failed transiently
+    pub entry_retries: u32,
+    pub request_retries: u64,
+    pub retry_base_delay: Duration,
+    pub retry_max_delay: Duration,
+    /// Requests per second allowed for each host
+    pub rate_limits: HashMap<String, f64>,
+    /// Requests per second allowed for hosts without their own limit
+    pub default_rate_limit: f64,
+    /// Look up studio and staff names and keep them in the studio table
+    pub name_lookup: bool,
+    /// Write company names instead of ids, needs the name lookup
+    pub company_names: bool,
+    pub browser_retries: u32,
+    /// Chrome tabs open at the same time
+    pub browser_tabs: usize,
+    /// Failed page loads in a row after which the browser is restarted
+    pub browser_max_failures_in_a_row: u32,
+    /// Time a tab gets for each browser call
+    pub page_timeout: Duration,
+    /// Time an HTTP request gets for each attempt
+    pub request_timeout: Duration,
+    /// Time an HTTP request gets to connect to the server
+    pub connect_timeout: Duration,
+    pub cache_dir: Option<PathBuf>,
+    /// Serve every page from the cache and never touch the network
+    pub offline: bool,
+    /// Fetch the subtitle files too, for the content columns
+    pub download_subtitles: bool,
+    /// Download zip and 7z files too, to list the files inside
+    pub open_archives: bool,
+}
+
+/// Builds the fetcher described by the config and crawls with it.
+pub async fn run(config: &Config) -> Result<()> {
+    if config.jimaku_source == JimakuSource::Api
+        && config.jimaku_api_key.is_none()
+        && !config.offline
+    {
+        bail!(
+            "The jimaku API needs an API key, set {} or pass --jimaku-api-key",
+            jimaku::api::API_KEY_VAR
+        );
+    }
+
+    if config.offline {
+        let cache_dir = config
+            .cache_dir
+            .as_ref()
+            .context("Offline mode requires a cache directory")?;
+
+        return crawl(config, &FileFetcher::new(PageCache::new(cache_dir))).await;
+    }
+
+    let policy = RetryPolicy {
+        retries: config.request_retries,
+        base_delay: config.retry_base_delay,
+        max_delay: config.retry_max_delay,
+    };
+    let limiter = Arc::new(RateLimiter::new(
+        config.rate_limits.clone(),
+        config.default_rate_limit,
+    ));
+
+    let fetcher = CombinedFetcher::new(
+        HttpFetcher::new(
+            policy,
+            limiter.clone(),
+            config.request_timeout,
+            config.connect_timeout,
+        )?,
+        ChromeFetcher::new(
+            config.browser_retries,
+            limiter,
+            config.browser_tabs,
+            config.browser_max_failures_in_a_row,
+            config.page_timeout,
+        ),
+    );
+
+    match &config.cache_dir {
+        Some(cache_dir) => {
+            crawl(
+                config,
+                // the listing gains entries, so it is fetched again for every
+                // crawl, while an incremental crawl needs the current pages
+                // of the entries that changed too
+                &CachedFetcher::new(fetcher, PageCache::new(cache_dir))
+                    .with_fresh_urls(config.listing_urls.clone())
+                    .with_refresh(config.mode == Mode::Incremental),
+            )
+            .await
+        }
+        None => crawl(config, &fetcher).await,
+    }
+}
+
+/// Crawls every pending entry and prints a run report, also when the crawl
+/// is aborted.
+pub async fn crawl<F: Fetcher>(config: &Config, fetcher: &F) -> Result<()> {
+    let mut report = Report::default();
+
+    let result = crawl_entries(config, fetcher, &mut report).await;
+
+    report.counters = fetcher.counters();
+    println!("{report}");
+
+    result
+}
+
+async fn crawl_entries<F: Fetcher>(
+    config: &Config,
+    fetcher: &F,
+    report: &mut Report,
+) -> Result<()> {
+    if config.mode == Mode::Restart {
+        remove_file_if_exists(&config.output_path)?;
+        for table in tables(config) {
+            remove_file_if_exists(table)?;
+        }
+    }
+
+    check_saved_header::<TsvEntry>(&config.output_path)?;
+
+    let entries = get_jimaku_entries(config, fetcher, &config.listing_urls)
+        .await
+        .context("Failed to get jimaku entries")?;
+
+    let changed = if config.mode == Mode::Incremental {
+        let refresh = Refresh::prepare(&config.output_path, &entries, config.complete_listing)
+            .context("Failed to prepare the incremental crawl")?;
+        report.refreshed = refresh.changed.len();
+        report.removed = refresh.removed;
+        refresh.changed
+    } else {
+        HashSet::new()
+    };
+
+    let saved_ids =
+        load_saved_ids(&config.output_path).context("Failed to load already saved entries")?;
+
+    if !saved_ids.is_empty() {
+        println!("resuming, {} entries already saved", saved_ids.len());
+    }
+
+    let mut pending: Vec<_> = entries
+        .iter()
+        .filter(|entry| {
+            (!saved_ids.contains(&entry.id) || changed.contains(&entry.id))
+                && entry.anilist_id.is_some()
+        })
+        .collect();
+
+    // a changed entry that lost its anilist id keeps its saved row, so it is
+    // reported instead of skipped like a new one
+    for entry in &entries {
+        if changed.contains(&entry.id) && entry.anilist_id.is_none() {
+            let failure = Failure::new(&jimaku::error::ParseError::NoAnilistId.into());
+            eprintln!("Failed to get entry {}, {}", entry.id, failure.message);
+            report.record(&failure);
+        }
+    }
+
+    report.listed = entries.len();
+    report.already_saved = entries
+        .iter()
+        .filter(|entry| saved_ids.contains(&entry.id) && !changed.contains(&entry.id))
+        .count();
+
+    let mut lookup = if config.name_lookup {
+        Some(NameLookup::load(relation_path(
+            &config.output_path,
+            "studios",
+        ))?)
+    } else {
+        None
+    };
+
+    let mut retries = config.entry_retries;
+
+    loop {
+        let failed =
+            crawl_pending(config, fetcher, pending, &changed, lookup.as_mut(), report).await?;
+
+        if failed.is_empty() || retries == 0 {
+            for (_, failure) in &failed {
+                report.record(failure);
+            }
+
+            break;
+        }
+
+        println!("retrying {} entries", failed.len());
+        pending = failed.into_iter().map(|(entry, _)| entry).collect();
+        retries -= 1;
+    }
+
+    Ok(())
+}
+
+/// Crawls the entries and saves them, returning the ones that failed in a
+/// way that may pass when tried again. The rows of `changed` entries are
+/// replaced once the entries are crawled, also when the crawl is aborted.
+async fn crawl_pending<'a, F: Fetcher>(
+    config: &Config,
+    fetcher: &F,
+    pending: Vec<&'a jimaku::entry::Entry>,
+    changed: &HashSet<i32>,
+    mut lookup: Option<&mut NameLookup>,
+    report: &mut Report,
+) -> Result<Vec<(&'a jimaku::entry::Entry, Failure)>> {
+    let batch_size = match config.anilist_source {
+        AnilistSource::Page => 1,
+        AnilistSource::Api => config.anilist_batch_size.max(1),
+    };
+
+    let concurrency = config.concurrency.max(1);
+
+    // jimaku files and anilist entries are fetched for several entries at
+    // once, while `buffered` keeps the results in listing order for the writer
+    let mut batches = stream::iter(pending)
+        .map(|entry| async move {
+            let files_data = get_jimaku_entry_files_data(config, fetcher, entry)
+                .await
+                .context("Failed to get jimaku entry files data");
+
+            (entry, files_data)
+        })
+        .buffered(concurrency)
+        .chunks(batch_size)
+        .map(|batch| get_batch_anilist_entries(config, fetcher, batch, changed))
+        .buffered(concurrency);
+
+    let mut failed = Vec::new();
+    let mut failed_in_a_row = 0;
+    let mut is_aborted = false;
+    let mut replaced = Vec::new();
+
+    'batches: while let Some(mut batch) = batches.next().await {
+        if let Some(lookup) = lookup.as_deref_mut() {
+            resolve_names(config, fetcher, lookup, &mut batch).await;
+        }
+
+        let company_names = lookup
+            .as_deref()
+            .filter(|_| config.company_names)
+            .map(NameLookup::names);
+
+        for fetched in batch {
+            if config.max_entries.is_some_and(|max| report.saved >= max) {
+                failed.clear();
+                break 'batches;
+            }
+
+            let entry = fetched.entry;
+
+            let rows = fetched.data.and_then(|(files_data, anilist_data)| {
+                let tsv_entry = get_tsv_entry(entry, &files_data, &anilist_data, company_names)
+                    .map_err(|err| Failure::new(&err))?;
+
+                report.record_unknown_values(&anilist_data);
+
+                Ok(EntryRows {
+                    tsv_entry,
+                    company_rows: get_company_rows(entry.id, &anilist_data),
+                    tag_rows: get_tag_rows(entry.id, &anilist_data),
+                    relation_rows: get_relation_rows(entry.id, fetched.anilist_id, &anilist_data),
+                    archive_rows: get_archive_rows(entry.id, &files_data),
+                })
+            });
+
+            let failure = match rows {
+                Ok(rows) => {
+                    if changed.contains(&entry.id) {
+                        replaced.push(rows);
+                    } else {
+                        save_entry_rows(config, &rows)?;
+                    }
+                    failed_in_a_row = 0;
+
+                    if report.saved.is_multiple_of(10) {
+                        println!("{}", report.saved);
+                    }
+                    report.saved += 1;
+                    continue;
+                }
+                Err(failure) => failure,
+            };
+
+            eprintln!(
+                "Failed to get entry {} (anilist {}), {}: {}",
+                entry.id, fetched.anilist_id, failure.category, failure.message
+            );
+
+            if failure.permanent {
+                report.record(&failure);
+                continue;
+            }
+
+            failed_in_a_row += 1;
+            failed.push((entry, failure));
+
+            if failed_in_a_row == config.max_failures_in_a_row {
+                is_aborted = true;
+                break 'batches;
+            }
+        }
+    }
+
+    replace_entry_rows(config, replaced)?;
+
+    if is_aborted {
+        for (_, failure) in &failed {
+            report.record(failure);
+        }
+
+        bail!("Failed to get {failed_in_a_row} entries in a row");
+    }
+
+    Ok(failed)
+}
+
+/// The rows saved for an entry, in the output and in each table.
+struct EntryRows {
+    tsv_entry: TsvEntry,
+    company_rows: Vec<CompanyRow>,
+    tag_rows: Vec<TagRow>,
+    relation_rows: Vec<RelationRow>,
+    archive_rows: Vec<ArchiveRow>,
+}
+
+/// Appends the rows of an entry that is not saved yet.
+fn save_entry_rows(config: &Config, rows: &EntryRows) -> Result<()> {
+    // the entry row goes last, as it marks the entry as saved
+    save_rows_to_tsv(&rows.company_rows, companies_path(config))?;
+    save_rows_to_tsv(&rows.tag_rows, tags_path(config))?;
+    save_rows_to_tsv(&rows.relation_rows, relations_path(config))?;
+    save_rows_to_tsv(&rows.archive_rows, archives_path(config))?;
+    save_to_tsv(&rows.tsv_entry, &config.output_path, &config.missing_marker)
+}
+
+/// Replaces the saved rows of changed entries, rewriting the output and each
+/// table once for all of them.
+fn replace_entry_rows(config: &Config, replaced: Vec<EntryRows>) -> Result<()> {
+    if replaced.is_empty() {
+        return Ok(());
+    }
+
+    let ids: HashSet<i32> = replaced
+        .iter()
+        .map(|rows| rows.tsv_entry.jimaku_id)
+        .collect();
+
+    for table in tables(config) {
+        remove_saved_rows(table, &ids)?;
+    }
+
+    let mut tsv_entries = Vec::new();
+    let mut company_rows = Vec::new();
+    let mut tag_rows = Vec::new();
+    let mut relation_rows = Vec::new();
+    let mut archive_rows = Vec::new();
+
+    for rows in replaced {
+        tsv_entries.push(rows.tsv_entry);
+        company_rows.extend(rows.company_rows);
+        tag_rows.extend(rows.tag_rows);
+        relation_rows.extend(rows.relation_rows);
+        archive_rows.extend(rows.archive_rows);
+    }
+
+    // the entry rows go last, as the old ones mark the entries as changed
+    save_rows_to_tsv(&company_rows, companies_path(config))?;
+    save_rows_to_tsv(&tag_rows, tags_path(config))?;
+    save_rows_to_tsv(&relation_rows, relations_path(config))?;
+    save_rows_to_tsv(&archive_rows, archives_path(config))?;
+    replace_saved_entries(&tsv_entries, &config.output_path, &config.missing_marker)
+}
+
+/// Looks up the names of the companies of the batch. Entries fail when the
+/// names cannot be fetched and they are written to the output, otherwise the
+/// names are only missing from the studio table.
+async fn resolve_names<F: Fetcher>(
+    config: &Config,
+    fetcher: &F,
+    lookup: &mut NameLookup,
+    batch: &mut [FetchedEntry<'_>],
+) {
+    let company_rows: Vec<_> = batch
+        .iter()
+        .filter_map(|fetched| {
+            let (_, anilist_data) = fetched.data.as_ref().ok()?;
+            Some(get_company_rows(fetched.entry.id, anilist_data))
+        })
+        .flatten()
+        .collect();
+
+    let Err(err) = lookup
+        .resolve(fetcher, &config.anilist_api_url, &company_rows)
+        .await
+    else {
+        return;
+    };
+
+    let err = err.context("Failed to look up company names");
+
+    if !config.company_names {
+        eprintln!("{err:#}");
+        return;
+    }
+
+    let failure = Failure::new(&err);
+
+    for fetched in batch.iter_mut() {
+        if fetched.data.is_ok() {
+            fetched.data = Err(failure.clone());
+        }
+    }
+}
+
+/// Tables saved along with the output, which hold rows of the entries.
+fn tables(config: &Config) -> Vec<PathBuf> {
+    vec![
+        companies_path(config),
+        tags_path(config),
+        relations_path(config),
+        archives_path(config),
+    ]
+}
+
+fn companies_path(config: &Config) -> PathBuf {
+    relation_path(&config.output_path, "companies")
+}
+
+fn tags_path(config: &Config) -> PathBuf {
+    relation_path(&config.output_path, "tags")
+}
+
+fn relations_path(config: &Config) -> PathBuf {
+    relation_path(&config.output_path, "relations")
+}
+
+fn archives_path(config: &Config) -> PathBuf {
+    relation_path(&config.output_path, "archives")
+}
+
+/// A jimaku entry with everything fetched for it, ready to be saved.
+struct FetchedEntry<'a> {
+    entry: &'a jimaku::entry::Entry,
+    anilist_id: i32,
+    data: Result<(Vec<FileData>, anilist::entry::Entry), Failure>,
+}
+
+/// Gets the anilist entries for a batch of jimaku entries, skipping the new
+/// ones without files. Changed entries without files fail, as they keep
+/// their saved rows.
+async fn get_batch_anilist_entries<'a, F: Fetcher>(
+    config: &Config,
+    fetcher: &F,
+    batch: Vec<(&'a jimaku::entry::Entry, Result<Vec<FileData>>)>,
+    changed: &HashSet<i32>,
+) -> Vec<FetchedEntry<'a>> {
+    let ready: Vec<_> = batch
+        .into_iter()
+        .filter_map(|(entry, files_data)| {
+            let anilist_id = entry.anilist_id?;
+
+            match files_data {
+                Ok(files_data) if files_data.is_empty() => changed.contains(&entry.id).then(|| {
+                    let failure = Failure::new(&jimaku::error::ParseError::NoFiles.into());
+                    (entry, anilist_id, Err(failure))
+                }),
+                Ok(files_data) => Some((entry, anilist_id, Ok(files_data))),
+                Err(err) => Some((entry, anilist_id, Err(Failure::new(&err)))),
+            }
+        })
+        .collect();
+
+    let ids: Vec<_> = ready
+        .iter()
+        .filter(|(_, _, files_data)| files_data.is_ok())
+        .map(|&(_, anilist_id, _)| anilist_id)
+        .collect();
+    let anilist_entries = get_anilist_entries(config, fetcher, &ids).await;
+
+    // jimaku lists some anime more than once, and each of those entries
+    // gets the anilist entry
+    ready
+        .into_iter()
+        .map(|(entry, anilist_id, files_data)| FetchedEntry {
+            entry,
+            anilist_id,
+            data: files_data.and_then(|files_data| {
+                let anilist_data =
+                    anilist_entries
+                        .get(&anilist_id)
+                        .cloned()
+                        .unwrap_or_else(|| {
+                            Err(Failure::new(
+                                &anilist::error::ParseError::NotFound(anilist_id).into(),
+                            ))
+                        })?;
+
+                Ok((files_data, anilist_data))
+            }),
+        })
+        .collect()
+}
+
+/// Gets the anilist entries for all the ids, with a single request per batch
+/// when the API is used.
+async fn get_anilist_entries<F: Fetcher>(
+    config: &Config,
+    fetcher: &F,
+    anilist_ids: &[i32],
+) -> HashMap<i32, Result<anilist::entry::Entry, Failure>> {
+    if anilist_ids.is_empty() {
+        return HashMap::new();
+    }
+
+    match config.anilist_source {
+        AnilistSource::Api => {
+            match api::get_anilist_entries(fetcher, &config.anilist_api_url, anilist_ids).await {
+                Ok(entries) => entries
+                    .into_iter()
+                    .map(|(anilist_id, entry)| {
+                        (anilist_id, entry.map_err(|err| Failure::new(&err)))
+                    })
+                    .collect(),
+                Err(err) => {
+                    let failure = Failure::new(&err);
+
+                    anilist_ids
+                        .iter()
+                        .map(|&anilist_id| (anilist_id, Err(failure.clone())))
+                        .collect()
+                }
+            }
+        }
+        AnilistSource::Page => {
+            let mut entries = HashMap::new();
+
+            for &anilist_id in anilist_ids {
+                let entry = get_anilist_page_entry(config, fetcher, anilist_id)
+                    .await
+                    .map_err(|err| Failure::new(&err));
+                entries.insert(anilist_id, entry);
+            }
+
+            entries
+        }
+    }
+}
+
+async fn get_anilist_page_entry<F: Fetcher>(
+    config: &Config,
+    fetcher: &F,
+    anilist_id: i32,
+) -> Result<anilist::entry::Entry> {
+    let url = format!("{}/anime/{anilist_id}", config.anilist_url);
+
+    let (head, body) = fetcher.get_page(&url).await?;
+
+    let anilist_entry = parse_anilist_entry(&head, &body)
+        .map_err(
+            |err| match err.downcast_ref::<anilist::error::ParseError>() {
+                // the browser may hand over a page before it finished rendering,
+                // so loading it again can find the field
+                Some(&anilist::error::ParseError::MissingField(field)) => {
+                    anilist::error::ParseError::NotRendered(field).into()
+                }
+                _ => err,
+            },
+        )
+        .context("Failed to parse request head")?;
+
+    Ok(anilist_entry)
+}
+
+async fn get_jimaku_entry_files_data<F: Fetcher>(
+    config: &Config,
+    fetcher: &F,
+    entry: &jimaku::entry::Entry,
+) -> Result<Vec<FileData>> {
+    let mut files_data = match config.jimaku_source {
+        JimakuSource::Api => {
+            jimaku::api::get_files(
+                fetcher,
+                &config.jimaku_url,
+                entry.id,
+                config.jimaku_api_key.as_deref(),
+            )
+            .await?
+        }
+        JimakuSource::Page => {
+            let url = format!("{}/entry/{}", config.jimaku_url, entry.id);
+
+            let body = fetcher
+                .get_body(&url)
+                .await
+                .context("Failed to get request body")?;
+
+            parse_files_data(&body).context("Failed to parse request body")?
+        }
+    };
+
+    if config.download_subtitles || config.open_archives {
+        download_files(config, fetcher, &mut files_data).await?;
+    }
+
+    Ok(files_data)
+}
+
+/// Downloads the subtitle files of an entry to read their cues, and the
+/// archives to list the files inside. Files that are gone or archives that
+/// cannot be opened are skipped, while other errors fail the entry so it is
+/// tried again.
+async fn download_files<F: Fetcher>(
+    config: &Config,
+    fetcher: &F,
+    files_data: &mut [FileData],
+) -> Result<()> {
+    for file in files_data {
+        let Some(href) = file.url.as_deref() else {
+            continue;
+        };
+
+        let Some(extension) = parse_file_name(&file.name).extension else {
+            continue;
+        };
+
+        let is_subtitle =
+            config.download_subtitles && SubtitleFormat::from_extension(&extension).is_some();
+        let archive_format =
+            ArchiveFormat::from_extension(&extension).filter(|_| config.open_archives);
+
+        if !is_subtitle && archive_format.is_none() {
+            continue;
+        }
+
+        let url = if href.starts_with('/') {
+            format!("{}{href}", config.jimaku_url)
+        } else {
+            href.to_string()
+        };
+
+        let bytes = match fetcher.get_bytes(&url).await {
+            Ok(bytes) => bytes,
+            Err(err) if Failure::new(&err).permanent => {
+                eprintln!("Skipping file {url}: {err:#}");
+                continue;
+            }
+            Err(err) => return Err(err.context("Failed to download file")),
+        };
+
+        if is_subtitle {
+            file.cues = Some(parse_subtitle(&bytes, &extension));
+        }
+
+        if let Some(format) = archive_format {
+            let read_content = |path: &str| {
+                config.download_subtitles
+                    && parse_file_name(path).extension.is_some_and(|extension| {
+                        SubtitleFormat::from_extension(&extension).is_some()
+                    })
+            };
+
+            match read_archive(&bytes, format, read_content) {
+                Ok(members) => {
+                    file.members = Some(
+                        members
+                            .into_iter()
+                            .map(|member| to_member_file_data(file, member))
+                            .collect(),
+                    );
+                }
+                Err(err) => eprintln!("Skipping archive {url}: {:#}", anyhow::Error::from(err)),
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Describes a file inside an archive like the files listed on the entry
+/// page, taking the time of the archive when the member has none.
+fn to_member_file_data(archive: &FileData, member: ArchiveMember) -> FileData {
+    let cues = member.content.as_deref().and_then(|content| {
+        let extension = parse_file_name(&member.path).extension?;
+        Some(parse_subtitle(content, &extension))
+    });
+
+    FileData {
+        last_modified: member.last_modified.map_or_else(
+            || archive.last_modified.clone(),
+            |time| time.and_utc().to_rfc3339(),
+        ),
+        name: member.path,
+        size: i64::try_from(member.size).unwrap_or(i64::MAX),
+        url: None,
+        cues,
+        members: None,
+    }
+}
+
+async fn get_jimaku_entries<F: Fetcher>(
+    config: &Config,
+    fetcher: &F,
+    urls: &[String],
+) -> Result<Vec<jimaku::entry::Entry>> {
+    let tasks = urls.iter().map(|url| async move {
+        let entries = match config.jimaku_source {
+            JimakuSource::Api => {
+                jimaku::api::get_entries(fetcher, url, config.jimaku_api_key.as_deref()).await?
+            }
+            JimakuSource::Page => {
+                let body = fetcher
+                    .get_body(url)
+                    .await
+                    .context("Failed to get request body")?;
+
+                parse_entries(&body).context("Failed to parse request body")?
+            }
+        };
+
+        let len = entries.len();
+        println!("{len}");
+
+        Ok::<Vec<jimaku::entry::Entry>, anyhow::Error>(entries)
+    });
+
+    let all_entries = try_join_all(tasks).await?.into_iter().flatten().collect();
+
+    Ok(all_entries)
+}
@@ -0,0 +1,51 @@
+use std::error::Error;
+
+use crate::{
+    convert::error::ConvertError,
+    parse::{anilist, jimaku},
+    request::error::FetchError,
+};
+
+/// Why an entry could not be saved, sorted into a category of the failure
+/// summary.
+#[derive(Debug, Clone)]
+pub struct Failure {
+    pub category: &'static str,
+    /// Trying the entry again cannot help, so it is skipped without counting
+    /// toward the failures in a row.
+    pub permanent: bool,
+    pub message: String,
+}
+
+impl Failure {
+    /// Classifies the error by the first typed error in its chain. Errors of
+    /// any other type are treated as transient.
+    #[must_use]
+    pub fn new(err: &anyhow::Error) -> Self {
+        let (category, permanent) = err.chain().find_map(classify).unwrap_or(("other", false));
+
+        Failure {
+            category,
+            permanent,
+            message: format!("{err:#}"),
+        }
+    }
+}
+
+fn classify(cause: &(dyn Error + 'static)) -> Option<(&'static str, bool)> {
+    if let Some(err) = cause.downcast_ref::<FetchError>() {
+        return Some((err.category(), err.is_permanent()));
+    }
+
+    if let Some(err) = cause.downcast_ref::<anilist::error::ParseError>() {
+        return Some((err.category(), err.is_permanent()));
+    }
+
+    if let Some(err) = cause.downcast_ref::<jimaku::error::ParseError>() {
+        return Some((err.category(), true));
+    }
+
+    cause
+        .downcast_ref::<ConvertError>()
+        .map(|err| (err.category(), true))
+}
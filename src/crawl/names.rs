@@ -0,0 +1,96 @@
+use std::{
+    collections::{BTreeSet, HashSet},
+    path::PathBuf,
+};
+
+use anyhow::Result;
+
+use crate::{
+    convert::company::{CompanyNames, CompanyRow, NameKind, NameRow},
+    fetch::Fetcher,
+    parse::anilist::api,
+    storage::{load_rows_from_tsv, save_rows_to_tsv},
+};
+
+/// Resolves studio and staff ids to names with the anilist API.
+///
+/// Every name is appended to the studio dimension table, which is read back
+/// on the next run, so an id is only ever asked for once. The table does not
+/// depend on the crawled entries and survives a restart.
+pub struct NameLookup {
+    path: PathBuf,
+    names: CompanyNames,
+    /// Ids the API did not know, which are not asked for again in this run
+    unknown: HashSet<(NameKind, i32)>,
+}
+
+impl NameLookup {
+    pub fn load(path: PathBuf) -> Result<Self> {
+        let rows = if path.exists() {
+            load_rows_from_tsv(&path)?
+        } else {
+            Vec::new()
+        };
+
+        Ok(NameLookup {
+            path,
+            names: CompanyNames::new(rows),
+            unknown: HashSet::new(),
+        })
+    }
+
+    #[must_use]
+    pub fn names(&self) -> &CompanyNames {
+        &self.names
+    }
+
+    /// Fetches the names of the companies not known yet.
+    pub async fn resolve<F: Fetcher>(
+        &mut self,
+        fetcher: &F,
+        api_url: &str,
+        rows: &[CompanyRow],
+    ) -> Result<()> {
+        for kind in [NameKind::Studio, NameKind::Staff] {
+            let missing: Vec<_> = rows
+                .iter()
+                .filter(|row| row.role.kind() == kind)
+                .map(|row| row.company_id)
+                .filter(|&id| {
+                    self.names.get(kind, id).is_none() && !self.unknown.contains(&(kind, id))
+                })
+                .collect::<BTreeSet<_>>()
+                .into_iter()
+                .collect();
+
+            if missing.is_empty() {
+                continue;
+            }
+
+            let mut found = match kind {
+                NameKind::Studio => api::get_studio_names(fetcher, api_url, &missing).await?,
+                NameKind::Staff => api::get_staff_names(fetcher, api_url, &missing).await?,
+            };
+
+            let new_rows: Vec<_> = missing
+                .into_iter()
+                .filter_map(|id| {
+                    let Some(name) = found.remove(&id) else {
+                        self.unknown.insert((kind, id));
+                        return None;
+                    };
+
+                    Some(NameRow { kind, id, name })
+                })
+                .collect();
+
+            save_rows_to_tsv(&new_rows, &self.path)?;
+
+            for row in new_rows {
+                self.names.insert(row);
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,104 @@
+use std::{
+    collections::{HashMap, HashSet},
+    path::Path,
+};
+
+use anyhow::Result;
+
+use crate::{
+    parse::jimaku,
+    storage::{column_index, load_saved_column, parse_id, rewrite_rows, ID_COLUMN},
+};
+
+const MODIFIED_COLUMN: &str = "last_modified";
+const REMOVED_COLUMN: &str = "removed";
+
+/// An incremental crawl, which only fetches the entries that are new or
+/// changed since they were saved.
+///
+/// Changed entries keep their rows until they are fetched again, and the
+/// new rows then take their place, so an entry that fails or a crawl that
+/// is aborted leaves the rows as they were.
+pub struct Refresh {
+    /// Saved entries whose listing changed since
+    pub changed: HashSet<i32>,
+    /// Saved entries that are no longer listed, only known when the listing
+    /// is complete
+    pub removed: Option<usize>,
+}
+
+impl Refresh {
+    /// Finds the saved entries that changed on the listing, and marks the
+    /// ones missing from `entries` as removed when they are every entry on
+    /// jimaku. A listing of a section or search says nothing about the
+    /// entries outside it, so their removed column is left as it is.
+    pub fn prepare(
+        output_path: &Path,
+        entries: &[jimaku::entry::Entry],
+        is_complete_listing: bool,
+    ) -> Result<Self> {
+        let modified: HashMap<i32, Option<i64>> = load_saved_column(output_path, MODIFIED_COLUMN)?
+            .into_iter()
+            .map(|(id, value)| (id, value.parse::<i64>().ok()))
+            .collect();
+
+        let changed = entries
+            .iter()
+            .filter(|entry| {
+                modified
+                    .get(&entry.id)
+                    .is_some_and(|&saved| saved != Some(entry.last_modified))
+            })
+            .map(|entry| entry.id)
+            .collect();
+
+        let removed = if is_complete_listing {
+            Some(mark_removed(output_path, entries)?)
+        } else {
+            None
+        };
+
+        Ok(Refresh { changed, removed })
+    }
+}
+
+/// Sets the removed column of every saved entry by whether it is missing
+/// from `entries`, returning how many are.
+fn mark_removed(output_path: &Path, entries: &[jimaku::entry::Entry]) -> Result<usize> {
+    let listed: HashSet<i32> = entries.iter().map(|entry| entry.id).collect();
+    let mut removed = 0;
+
+    rewrite_rows(output_path, |headers, records, file_path| {
+        let id_index = column_index(headers, ID_COLUMN, file_path)?;
+        let removed_index = column_index(headers, REMOVED_COLUMN, file_path)?;
+
+        records
+            .into_iter()
+            .map(|record| {
+                let is_removed = !listed.contains(&parse_id(&record, id_index)?);
+
+                if is_removed {
+                    removed += 1;
+                }
+
+                Ok(record
+                    .iter()
+                    .enumerate()
+                    .map(|(index, value)| {
+                        if index == removed_index {
+                            if is_removed {
+                                "true"
+                            } else {
+                                "false"
+                            }
+                        } else {
+                            value
+                        }
+                    })
+                    .collect())
+            })
+            .collect()
+    })?;
+
+    Ok(removed)
+}
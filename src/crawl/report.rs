@@ -0,0 +1,95 @@
+use std::{
+    collections::BTreeMap,
+    fmt::{self, Display, Formatter},
+};
+
+use super::failure::Failure;
+use crate::parse::anilist;
+
+/// What a crawl did, printed when it ends.
+#[derive(Debug, Default)]
+pub struct Report {
+    /// Entries found on the listing pages
+    pub listed: usize,
+    /// Entries skipped because they were saved by an earlier run
+    pub already_saved: usize,
+    /// Saved entries crawled again because their listing changed
+    pub refreshed: usize,
+    /// Saved entries no longer listed, only counted by an incremental crawl
+    /// of the complete listing
+    pub removed: Option<usize>,
+    pub saved: usize,
+    /// Entries that could not be saved, by failure category
+    pub failures: BTreeMap<&'static str, usize>,
+    /// Failed entries that were skipped because trying again cannot help
+    pub permanent_failures: usize,
+    /// Values of anilist fields none of the enums know, with the entries
+    /// using them
+    pub unknown_values: BTreeMap<(&'static str, String), usize>,
+    /// Counters of the fetcher, like browser tabs and restarts
+    pub counters: Vec<(&'static str, u64)>,
+}
+
+impl Report {
+    /// Counts the values of the saved anilist entry that are not recognized.
+    pub fn record_unknown_values(&mut self, anilist_entry: &anilist::entry::Entry) {
+        for (field, value) in anilist_entry.unknown_values() {
+            *self
+                .unknown_values
+                .entry((field, value.to_string()))
+                .or_default() += 1;
+        }
+    }
+
+    /// Counts an entry that failed for good in this run.
+    pub fn record(&mut self, failure: &Failure) {
+        *self.failures.entry(failure.category).or_default() += 1;
+
+        if failure.permanent {
+            self.permanent_failures += 1;
+        }
+    }
+}
+
+impl Display for Report {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let failed: usize = self.failures.values().sum();
+
+        writeln!(f, "run report")?;
+        writeln!(f, "  entries listed: {}", self.listed)?;
+        writeln!(f, "  entries already saved: {}", self.already_saved)?;
+
+        if self.refreshed > 0 || self.removed.is_some() {
+            writeln!(f, "  entries refreshed: {}", self.refreshed)?;
+        }
+
+        if let Some(removed) = self.removed {
+            writeln!(f, "  entries removed: {removed}")?;
+        }
+
+        writeln!(f, "  entries saved: {}", self.saved)?;
+        write!(
+            f,
+            "  entries failed: {failed} ({} permanent)",
+            self.permanent_failures
+        )?;
+
+        for (category, count) in &self.failures {
+            write!(f, "\n    {category}: {count}")?;
+        }
+
+        if !self.unknown_values.is_empty() {
+            write!(f, "\n  unrecognized anilist values:")?;
+        }
+
+        for ((field, value), count) in &self.unknown_values {
+            write!(f, "\n    {field} {value}: {count}")?;
+        }
+
+        for (name, value) in &self.counters {
+            write!(f, "\n  {name}: {value}")?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,38 @@
+use std::future::Future;
+
+use anyhow::Result;
+
+pub mod cached;
+pub mod chrome;
+pub mod combined;
+pub mod file;
+pub mod http;
+pub mod scripted;
+
+/// Retrieves pages for the crawler.
+pub trait Fetcher: Sync {
+    /// Fetches the raw body of the page.
+    fn get_body(&self, url: &str) -> impl Future<Output = Result<String>> + Send;
+
+    /// Downloads a file as raw bytes.
+    fn get_bytes(&self, url: &str) -> impl Future<Output = Result<Vec<u8>>> + Send;
+
+    /// Gets a JSON API response, authorized with the API key if given. The
+    /// key is not part of what identifies the response.
+    fn get_json(
+        &self,
+        url: &str,
+        api_key: Option<&str>,
+    ) -> impl Future<Output = Result<String>> + Send;
+
+    /// Posts the JSON payload and returns the raw response body.
+    fn post_json(&self, url: &str, payload: &str) -> impl Future<Output = Result<String>> + Send;
+
+    /// Fetches the head and body of the page after it was rendered.
+    fn get_page(&self, url: &str) -> impl Future<Output = Result<(String, String)>> + Send;
+
+    /// Named counters of what the fetcher did, for the run report.
+    fn counters(&self) -> Vec<(&'static str, u64)> {
+        Vec::new()
+    }
+}
@@ -0,0 +1,118 @@
+use std::collections::HashSet;
+
+use anyhow::Result;
+
+use super::Fetcher;
+use crate::cache::PageCache;
+
+/// Serves pages from the page cache and stores everything fetched by the
+/// inner fetcher there.
+pub struct CachedFetcher<F> {
+    inner: F,
+    cache: PageCache,
+    /// Fetch every page again instead of serving it from the cache
+    refresh: bool,
+    /// Pages fetched again even without `refresh`, as they change
+    fresh_urls: HashSet<String>,
+}
+
+impl<F: Fetcher> CachedFetcher<F> {
+    #[must_use]
+    pub fn new(inner: F, cache: PageCache) -> Self {
+        CachedFetcher {
+            inner,
+            cache,
+            refresh: false,
+            fresh_urls: HashSet::new(),
+        }
+    }
+
+    /// Fetches every page from the inner fetcher and only stores it, for
+    /// crawls that have to see what changed since the pages were cached.
+    #[must_use]
+    pub fn with_refresh(mut self, refresh: bool) -> Self {
+        self.refresh = refresh;
+        self
+    }
+
+    /// Fetches these pages from the inner fetcher and only stores them, like
+    /// listings that gain entries while the pages of the entries stay.
+    #[must_use]
+    pub fn with_fresh_urls(mut self, urls: impl IntoIterator<Item = String>) -> Self {
+        self.fresh_urls.extend(urls);
+        self
+    }
+
+    fn cached<T>(
+        &self,
+        url: &str,
+        get: impl FnOnce(&PageCache) -> Result<Option<T>>,
+    ) -> Result<Option<T>> {
+        if self.refresh || self.fresh_urls.contains(url) {
+            return Ok(None);
+        }
+
+        get(&self.cache)
+    }
+}
+
+impl<F: Fetcher> Fetcher for CachedFetcher<F> {
+    async fn get_body(&self, url: &str) -> Result<String> {
+        if let Some(body) = self.cached(url, |cache| cache.get_body(url))? {
+            return Ok(body);
+        }
+
+        let body = self.inner.get_body(url).await?;
+        self.cache.put_body(url, &body)?;
+
+        Ok(body)
+    }
+
+    async fn get_bytes(&self, url: &str) -> Result<Vec<u8>> {
+        if let Some(bytes) = self.cached(url, |cache| cache.get_bytes(url))? {
+            return Ok(bytes);
+        }
+
+        let bytes = self.inner.get_bytes(url).await?;
+        self.cache.put_bytes(url, &bytes)?;
+
+        Ok(bytes)
+    }
+
+    async fn get_json(&self, url: &str, api_key: Option<&str>) -> Result<String> {
+        if let Some(body) = self.cached(url, |cache| cache.get_body(url))? {
+            return Ok(body);
+        }
+
+        let body = self.inner.get_json(url, api_key).await?;
+        self.cache.put_body(url, &body)?;
+
+        Ok(body)
+    }
+
+    async fn post_json(&self, url: &str, payload: &str) -> Result<String> {
+        if let Some(response) = self.cached(url, |cache| cache.get_response(url, payload))? {
+            return Ok(response);
+        }
+
+        let response = self.inner.post_json(url, payload).await?;
+        self.cache.put_response(url, payload, &response)?;
+
+        Ok(response)
+    }
+
+    async fn get_page(&self, url: &str) -> Result<(String, String)> {
+        if let Some(page) = self.cached(url, |cache| cache.get_page(url))? {
+            return Ok(page);
+        }
+
+        let (head, body) = self.inner.get_page(url).await?;
+        self.cache.put_page(url, &head, &body)?;
+
+        Ok((head, body))
+    }
+
+    fn counters(&self) -> Vec<(&'static str, u64)> {
+        self.inner.counters()
+    }
+}
@@ -0,0 +1,81 @@
+use std::{sync::Arc, time::Duration};
+
+use anyhow::{bail, Result};
+
+use self::pool::TabPool;
+use super::Fetcher;
+use crate::request::{error::FetchError, rate_limit::RateLimiter};
+
+pub mod pool;
+
+/// Renders pages in headless Chrome tabs, which are launched on the first
+/// request.
+pub struct ChromeFetcher {
+    retries: u32,
+    limiter: Arc<RateLimiter>,
+    pool: TabPool,
+}
+
+impl ChromeFetcher {
+    /// Creates a fetcher that loads pages in up to `tabs` tabs at once and
+    /// retries a failed load `retries` times.
+    #[must_use]
+    pub fn new(
+        retries: u32,
+        limiter: Arc<RateLimiter>,
+        tabs: usize,
+        max_failures_in_a_row: u32,
+        page_timeout: Duration,
+    ) -> Self {
+        ChromeFetcher {
+            retries,
+            limiter,
+            pool: TabPool::new(tabs, max_failures_in_a_row, page_timeout),
+        }
+    }
+}
+
+impl Fetcher for ChromeFetcher {
+    async fn get_body(&self, url: &str) -> Result<String> {
+        let (_, body) = self.get_page(url).await?;
+
+        Ok(body)
+    }
+
+    async fn get_bytes(&self, url: &str) -> Result<Vec<u8>> {
+        bail!("Cannot download {url} from the browser")
+    }
+
+    async fn get_json(&self, url: &str, _api_key: Option<&str>) -> Result<String> {
+        bail!("Cannot call the API at {url} from the browser")
+    }
+
+    async fn post_json(&self, url: &str, _payload: &str) -> Result<String> {
+        bail!("Cannot post to {url} from the browser")
+    }
+
+    async fn get_page(&self, url: &str) -> Result<(String, String)> {
+        let mut attempt = 0;
+
+        loop {
+            self.limiter.acquire(url).await;
+
+            match self.pool.get_page(url).await {
+                Ok(page) => return Ok(page),
+                Err(err) if attempt >= self.retries => {
+                    bail!(FetchError::Browser {
+                        url: url.to_string(),
+                        message: format!("{err:#}"),
+                    });
+                }
+                Err(err) => eprintln!("retrying {url} in a new tab: {err:#}"),
+            }
+
+            attempt += 1;
+        }
+    }
+
+    fn counters(&self) -> Vec<(&'static str, u64)> {
+        self.pool.counters()
+    }
+}
@@ -0,0 +1,226 @@
+use std::{
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use anyhow::{anyhow, Context, Result};
+use headless_chrome::{Browser, LaunchOptionsBuilder, Tab};
+use tokio::{
+    sync::{Mutex, Semaphore},
+    task::spawn_blocking,
+    time::timeout,
+};
+
+use crate::request::{close_tab_with_retry, get_page_data_chrome};
+
+/// How long an idle tab gets to answer before it is considered hung.
+const HEALTH_CHECK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Chrome tabs shared by every page load of a crawl.
+///
+/// Tabs are reused between pages. A tab that fails a load or stops answering
+/// is closed and replaced on its own, and the browser is only restarted once
+/// loads failed `max_failures_in_a_row` times in a row.
+pub struct TabPool {
+    max_failures_in_a_row: u32,
+    page_timeout: Duration,
+    permits: Semaphore,
+    state: Mutex<PoolState>,
+    counters: PoolCounters,
+}
+
+#[derive(Default)]
+struct PoolState {
+    browser: Option<Browser>,
+    idle: Vec<Arc<Tab>>,
+    failures_in_a_row: u32,
+}
+
+#[derive(Debug, Default)]
+struct PoolCounters {
+    pages_loaded: AtomicU64,
+    page_failures: AtomicU64,
+    tabs_created: AtomicU64,
+    tabs_reused: AtomicU64,
+    tabs_recycled: AtomicU64,
+    browser_restarts: AtomicU64,
+}
+
+impl TabPool {
+    /// Creates a pool of at most `size` tabs, where every browser call of a
+    /// tab times out after `page_timeout`. The browser is launched on the
+    /// first page load.
+    #[must_use]
+    pub fn new(size: usize, max_failures_in_a_row: u32, page_timeout: Duration) -> Self {
+        TabPool {
+            max_failures_in_a_row: max_failures_in_a_row.max(1),
+            page_timeout,
+            permits: Semaphore::new(size.max(1)),
+            state: Mutex::new(PoolState::default()),
+            counters: PoolCounters::default(),
+        }
+    }
+
+    /// Loads the page in a pooled tab and returns its head and full document.
+    pub async fn get_page(&self, url: &str) -> Result<(String, String)> {
+        let _permit = self.permits.acquire().await.context("Tab pool closed")?;
+
+        let tab = self.checkout().await?;
+
+        let loading_tab = tab.clone();
+        let url = url.to_string();
+        let result = spawn_blocking(move || get_page_data_chrome(&loading_tab, &url))
+            .await
+            .context("Page load panicked")
+            .and_then(|result| result);
+
+        match result {
+            Ok(page) => {
+                self.counters.pages_loaded.fetch_add(1, Ordering::Relaxed);
+
+                let mut state = self.state.lock().await;
+                state.failures_in_a_row = 0;
+                state.idle.push(tab);
+
+                Ok(page)
+            }
+            Err(err) => {
+                self.counters.page_failures.fetch_add(1, Ordering::Relaxed);
+                self.recycle(tab);
+                self.record_failure().await;
+
+                Err(err)
+            }
+        }
+    }
+
+    /// Counters for the run report.
+    #[must_use]
+    pub fn counters(&self) -> Vec<(&'static str, u64)> {
+        let counters = &self.counters;
+
+        [
+            ("pages loaded in the browser", &counters.pages_loaded),
+            ("browser page loads failed", &counters.page_failures),
+            ("browser tabs created", &counters.tabs_created),
+            ("browser tabs reused", &counters.tabs_reused),
+            ("browser tabs recycled", &counters.tabs_recycled),
+            ("browser restarts", &counters.browser_restarts),
+        ]
+        .into_iter()
+        .map(|(name, counter)| (name, counter.load(Ordering::Relaxed)))
+        .collect()
+    }
+
+    /// Takes a healthy idle tab, or opens a new one when there is none.
+    async fn checkout(&self) -> Result<Arc<Tab>> {
+        loop {
+            let idle = self.state.lock().await.idle.pop();
+
+            let Some(tab) = idle else {
+                return self.open_tab().await;
+            };
+
+            if is_healthy(&tab).await {
+                self.counters.tabs_reused.fetch_add(1, Ordering::Relaxed);
+                return Ok(tab);
+            }
+
+            eprintln!("recycling unresponsive browser tab");
+            self.recycle(tab);
+        }
+    }
+
+    /// Opens a tab, restarting the browser once if it cannot open one.
+    async fn open_tab(&self) -> Result<Arc<Tab>> {
+        let mut state = self.state.lock().await;
+
+        let mut restarted = false;
+
+        loop {
+            let browser = if let Some(browser) = &state.browser {
+                browser.clone()
+            } else {
+                let browser = spawn_blocking(get_new_browser)
+                    .await
+                    .context("Browser launch panicked")??;
+                state.browser.insert(browser).clone()
+            };
+
+            let page_timeout = self.page_timeout;
+            let tab = spawn_blocking(move || {
+                let tab = browser.new_tab()?;
+                tab.set_default_timeout(page_timeout);
+                Ok::<_, anyhow::Error>(tab)
+            })
+            .await
+            .context("Opening a tab panicked")?;
+
+            match tab {
+                Ok(tab) => {
+                    self.counters.tabs_created.fetch_add(1, Ordering::Relaxed);
+                    return Ok(tab);
+                }
+                Err(err) if restarted => return Err(err.context("Failed to create new tab")),
+                Err(err) => {
+                    eprintln!("restarting browser, failed to open a tab: {err:#}");
+                    self.restart(&mut state);
+                    restarted = true;
+                }
+            }
+        }
+    }
+
+    /// Closes a broken tab in the background, so a hung target cannot hold up
+    /// the crawl.
+    fn recycle(&self, tab: Arc<Tab>) {
+        self.counters.tabs_recycled.fetch_add(1, Ordering::Relaxed);
+
+        drop(spawn_blocking(move || close_tab_with_retry(&tab)));
+    }
+
+    async fn record_failure(&self) {
+        let mut state = self.state.lock().await;
+        state.failures_in_a_row += 1;
+
+        if state.failures_in_a_row >= self.max_failures_in_a_row {
+            eprintln!(
+                "restarting browser after {} failed page loads in a row",
+                state.failures_in_a_row
+            );
+            self.restart(&mut state);
+        }
+    }
+
+    /// Drops the browser and its idle tabs, so the next tab launches a new
+    /// one. Tabs still loading keep the old browser alive until they finish.
+    fn restart(&self, state: &mut PoolState) {
+        self.counters
+            .browser_restarts
+            .fetch_add(1, Ordering::Relaxed);
+
+        state.browser = None;
+        state.idle.clear();
+        state.failures_in_a_row = 0;
+    }
+}
+
+/// Whether the tab still runs scripts, which a crashed or hung target does
+/// not.
+async fn is_healthy(tab: &Arc<Tab>) -> bool {
+    let tab = tab.clone();
+    let check = spawn_blocking(move || tab.evaluate("1", false).is_ok());
+
+    matches!(timeout(HEALTH_CHECK_TIMEOUT, check).await, Ok(Ok(true)))
+}
+
+fn get_new_browser() -> Result<Browser> {
+    let launch_options = LaunchOptionsBuilder::default()
+        .build()
+        .map_err(|err| anyhow!("Failed to build launch options: {:?}", err))?;
+
+    Browser::new(launch_options).map_err(|err| anyhow!("Failed to create browser: {:?}", err))
+}
@@ -0,0 +1,45 @@
+use anyhow::Result;
+
+use super::Fetcher;
+
+/// Fetches raw bodies and posts with one fetcher and rendered pages with
+/// another.
+pub struct CombinedFetcher<B, P> {
+    bodies: B,
+    pages: P,
+}
+
+impl<B: Fetcher, P: Fetcher> CombinedFetcher<B, P> {
+    #[must_use]
+    pub fn new(bodies: B, pages: P) -> Self {
+        CombinedFetcher { bodies, pages }
+    }
+}
+
+impl<B: Fetcher, P: Fetcher> Fetcher for CombinedFetcher<B, P> {
+    async fn get_body(&self, url: &str) -> Result<String> {
+        self.bodies.get_body(url).await
+    }
+
+    async fn get_bytes(&self, url: &str) -> Result<Vec<u8>> {
+        self.bodies.get_bytes(url).await
+    }
+
+    async fn get_json(&self, url: &str, api_key: Option<&str>) -> Result<String> {
+        self.bodies.get_json(url, api_key).await
+    }
+
+    async fn post_json(&self, url: &str, payload: &str) -> Result<String> {
+        self.bodies.post_json(url, payload).await
+    }
+
+    async fn get_page(&self, url: &str) -> Result<(String, String)> {
+        self.pages.get_page(url).await
+    }
+
+    fn counters(&self) -> Vec<(&'static str, u64)> {
+        let mut counters = self.bodies.counters();
+        counters.extend(self.pages.counters());
+        counters
+    }
+}
@@ -0,0 +1,47 @@
+use anyhow::Result;
+
+use super::Fetcher;
+use crate::{cache::PageCache, request::error::FetchError};
+
+/// Serves pages only from the page cache, which makes the crawl work offline.
+pub struct FileFetcher {
+    cache: PageCache,
+}
+
+impl FileFetcher {
+    #[must_use]
+    pub fn new(cache: PageCache) -> Self {
+        FileFetcher { cache }
+    }
+}
+
+impl Fetcher for FileFetcher {
+    async fn get_body(&self, url: &str) -> Result<String> {
+        self.cache.get_body(url)?.ok_or_else(|| not_cached(url))
+    }
+
+    async fn get_bytes(&self, url: &str) -> Result<Vec<u8>> {
+        self.cache.get_bytes(url)?.ok_or_else(|| not_cached(url))
+    }
+
+    async fn get_json(&self, url: &str, _api_key: Option<&str>) -> Result<String> {
+        self.cache.get_body(url)?.ok_or_else(|| not_cached(url))
+    }
+
+    async fn post_json(&self, url: &str, payload: &str) -> Result<String> {
+        self.cache
+            .get_response(url, payload)?
+            .ok_or_else(|| not_cached(url))
+    }
+
+    async fn get_page(&self, url: &str) -> Result<(String, String)> {
+        self.cache.get_page(url)?.ok_or_else(|| not_cached(url))
+    }
+}
+
+fn not_cached(url: &str) -> anyhow::Error {
+    FetchError::NotCached {
+        url: url.to_string(),
+    }
+    .into()
+}
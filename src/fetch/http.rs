@@ -0,0 +1,73 @@
+use std::{sync::Arc, time::Duration};
+
+use anyhow::{anyhow, Context, Result};
+use scraper::{Html, Selector};
+
+use super::Fetcher;
+use crate::request::{
+    build_client, get_body, get_bytes, get_json, post_json, rate_limit::RateLimiter, RetryPolicy,
+};
+
+/// Fetches pages with plain HTTP requests, so pages are never rendered.
+#[derive(Debug, Clone)]
+pub struct HttpFetcher {
+    client: reqwest::Client,
+    policy: RetryPolicy,
+    limiter: Arc<RateLimiter>,
+}
+
+impl HttpFetcher {
+    /// Each request attempt gives up after `timeout`, and after
+    /// `connect_timeout` when the server does not accept the connection.
+    pub fn new(
+        policy: RetryPolicy,
+        limiter: Arc<RateLimiter>,
+        timeout: Duration,
+        connect_timeout: Duration,
+    ) -> Result<Self> {
+        Ok(HttpFetcher {
+            client: build_client(timeout, connect_timeout)?,
+            policy,
+            limiter,
+        })
+    }
+}
+
+impl Fetcher for HttpFetcher {
+    async fn get_body(&self, url: &str) -> Result<String> {
+        get_body(&self.client, url, &self.policy, &self.limiter).await
+    }
+
+    async fn get_bytes(&self, url: &str) -> Result<Vec<u8>> {
+        get_bytes(&self.client, url, &self.policy, &self.limiter).await
+    }
+
+    async fn get_json(&self, url: &str, api_key: Option<&str>) -> Result<String> {
+        get_json(&self.client, url, api_key, &self.policy, &self.limiter).await
+    }
+
+    async fn post_json(&self, url: &str, payload: &str) -> Result<String> {
+        post_json(&self.client, url, payload, &self.policy, &self.limiter).await
+    }
+
+    async fn get_page(&self, url: &str) -> Result<(String, String)> {
+        let body = get_body(&self.client, url, &self.policy, &self.limiter).await?;
+
+        split_document(&body)
+    }
+}
+
+fn split_document(data: &str) -> Result<(String, String)> {
+    let document = Html::parse_document(data);
+
+    let head_selector =
+        Selector::parse("head").map_err(|err| anyhow!("Failed to parse selector: {:?}", err))?;
+
+    let head = document
+        .select(&head_selector)
+        .next()
+        .context("Failed to find head element")?
+        .html();
+
+    Ok((head, data.to_string()))
+}
@@ -0,0 +1,120 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::Mutex,
+};
+
+use anyhow::{anyhow, bail, Result};
+
+use super::Fetcher;
+
+/// Either a rendered page as head and body, or a failure message.
+type Response = std::result::Result<(String, String), String>;
+
+/// Answers requests with canned responses, so the crawl can run without the
+/// real sites.
+///
+/// Responses for a URL are returned in the order they were added, and the
+/// last one keeps being returned once the others are used up.
+#[derive(Debug, Default)]
+pub struct ScriptedFetcher {
+    responses: Mutex<HashMap<String, VecDeque<Response>>>,
+    requests: Mutex<Vec<String>>,
+}
+
+impl ScriptedFetcher {
+    #[must_use]
+    pub fn new() -> Self {
+        ScriptedFetcher::default()
+    }
+
+    #[must_use]
+    pub fn with_body(self, url: &str, body: &str) -> Self {
+        self.push(url, Ok((String::new(), body.to_string())))
+    }
+
+    #[must_use]
+    pub fn with_page(self, url: &str, head: &str, body: &str) -> Self {
+        self.push(url, Ok((head.to_string(), body.to_string())))
+    }
+
+    #[must_use]
+    pub fn with_failure(self, url: &str, message: &str) -> Self {
+        self.push(url, Err(message.to_string()))
+    }
+
+    /// Returns every requested URL in request order.
+    pub fn requests(&self) -> Vec<String> {
+        self.requests
+            .lock()
+            .map(|requests| requests.clone())
+            .unwrap_or_default()
+    }
+
+    fn push(self, url: &str, response: Response) -> Self {
+        if let Ok(mut responses) = self.responses.lock() {
+            responses
+                .entry(url.to_string())
+                .or_default()
+                .push_back(response);
+        }
+
+        self
+    }
+
+    fn next(&self, url: &str) -> Result<(String, String)> {
+        if let Ok(mut requests) = self.requests.lock() {
+            requests.push(url.to_string());
+        }
+
+        let mut responses = self
+            .responses
+            .lock()
+            .map_err(|_| anyhow!("Scripted responses are poisoned"))?;
+
+        let Some(queue) = responses.get_mut(url) else {
+            bail!("No scripted response for {url}");
+        };
+
+        let response = if queue.len() > 1 {
+            queue.pop_front()
+        } else {
+            queue.front().cloned()
+        };
+
+        match response {
+            Some(Ok(page)) => Ok(page),
+            Some(Err(message)) => bail!("{message}"),
+            None => bail!("No scripted response for {url}"),
+        }
+    }
+}
+
+impl Fetcher for ScriptedFetcher {
+    async fn get_body(&self, url: &str) -> Result<String> {
+        let (_, body) = self.next(url)?;
+
+        Ok(body)
+    }
+
+    async fn get_bytes(&self, url: &str) -> Result<Vec<u8>> {
+        let (_, body) = self.next(url)?;
+
+        Ok(body.into_bytes())
+    }
+
+    async fn get_json(&self, url: &str, _api_key: Option<&str>) -> Result<String> {
+        let (_, body) = self.next(url)?;
+
+        Ok(body)
+    }
+
+    async fn post_json(&self, url: &str, _payload: &str) -> Result<String> {
+        let (_, body) = self.next(url)?;
+
+        Ok(body)
+    }
+
+    async fn get_page(&self, url: &str) -> Result<(String, String)> {
+        self.next(url)
+    }
+}
@@ -1,4 +1,7 @@
+pub mod cache;
 pub mod convert;
+pub mod crawl;
+pub mod fetch;
 pub mod parse;
 pub mod request;
 pub mod storage;
@@ -1,160 +1,418 @@
-use std::process::exit;
+use std::{fs, path::PathBuf, time::Duration};
 
-use anyhow::{anyhow, bail, Context, Result};
-use headless_chrome::{Browser, LaunchOptionsBuilder};
+use anyhow::{Context, Result};
+use clap::{Args, Parser, Subcommand, ValueEnum};
 use ml_parser::{
-    convert::entry::get_tsv_entry,
-    parse::{
-        anilist::{self, entry::parse_anilist_entry},
-        jimaku::{
-            self,
-            entry::parse_entries,
-            file::{parse_files_data, FileData},
-        },
-    },
-    request::{get_body, get_page_data_chrome},
-    storage::save_to_tsv,
+    convert::arff::{self, tsv_to_arff},
+    crawl,
+    parse::{anilist, arff::ARFFData, jimaku},
+    request::rate_limit,
+    storage,
 };
 
-#[tokio::main]
-async fn main() -> Result<()> {
-    let urls = ["https://jimaku.cc", "https://jimaku.cc/dramas"];
-
-    let entries = get_jimaku_entries(&urls)
-        .await
-        .context("Failed to get jimaku entries")?;
+#[derive(Debug, Parser)]
+#[command(about = "Collects jimaku and anilist data into a dataset")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
 
-    let mut browser = get_new_browser()?;
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Crawl jimaku entries and their anilist pages into a TSV file
+    Crawl(CrawlArgs),
+    /// Convert a crawled TSV file into ARFF
+    Convert(ConvertArgs),
+    /// Normalize an ARFF file into CSV
+    Normalize {
+        #[arg(short, long, default_value = "./data/data.arff")]
+        input: PathBuf,
+        #[arg(short, long, default_value = "./data/data.csv")]
+        output: PathBuf,
+    },
+}
 
-    let mut current = 0;
-    let mut failed_in_a_row = 0;
+#[derive(Debug, Args)]
+struct ConvertArgs {
+    #[arg(short, long, default_value = "./data/data.tsv")]
+    input: PathBuf,
+    #[arg(short, long, default_value = "./data/data.arff")]
+    output: PathBuf,
+
+    /// Value the input uses for a missing value, like "" for crawls that
+    /// left the field empty
+    #[arg(long, default_value = storage::MISSING_MARKER)]
+    missing_marker: String,
+
+    /// Company table of the crawl, defaults to companies.tsv next to the
+    /// input
+    #[arg(long)]
+    companies: Option<PathBuf>,
+
+    /// Companies of each role that get their own attribute
+    #[arg(long, default_value_t = 20)]
+    top_companies: usize,
+
+    /// Tag table of the crawl, defaults to tags.tsv next to the input
+    #[arg(long)]
+    tags: Option<PathBuf>,
+
+    /// Tags that get their own attribute, weighted by their rank
+    #[arg(long, default_value_t = 30)]
+    top_tags: usize,
+
+    /// Relation edge list of the crawl, defaults to relations.tsv next to
+    /// the input
+    #[arg(long)]
+    relations: Option<PathBuf>,
+}
 
-    for entry in &entries {
-        let anilist_id = match entry.anilist_id {
-            None => continue,
-            Some(anilist_id) => anilist_id,
-        };
+impl ConvertArgs {
+    fn options(&self) -> arff::Options {
+        let mut options = arff::Options::for_input(&self.input);
+        options.missing_marker.clone_from(&self.missing_marker);
 
-        let files_data = get_jimaku_entry_files_data(&entry)
-            .await
-            .context("Failed to get jimaku entry files data")?;
+        if let Some(companies) = &self.companies {
+            options.companies_path = Some(companies.clone());
+        }
+        options.top_companies = self.top_companies;
 
-        if files_data.is_empty() {
-            continue;
+        if let Some(tags) = &self.tags {
+            options.tags_path = Some(tags.clone());
         }
+        options.top_tags = self.top_tags;
 
-        let anilist_data = match get_anilist_entry(&mut browser, anilist_id).await {
-            Ok(anilist_data) => anilist_data,
-            Err(err) => {
-                eprintln!("Failed to get anilist entry {anilist_id}: {:#}", err);
-                failed_in_a_row += 1;
-
-                if failed_in_a_row == 5 {
-                    exit(1);
-                }
-                continue;
-            }
-        };
+        if let Some(relations) = &self.relations {
+            options.relations_path = Some(relations.clone());
+        }
 
-        failed_in_a_row = 0;
+        options
+    }
+}
 
-        let tsv_entry = get_tsv_entry(&entry, &files_data, &anilist_data)?;
+#[derive(Debug, Args)]
+#[allow(clippy::struct_excessive_bools)]
+struct CrawlArgs {
+    /// Jimaku listing page, or API search with --jimaku-source api, to
+    /// collect entries from, overrides --section
+    #[arg(long = "listing-url", value_name = "URL")]
+    listing_urls: Vec<String>,
+
+    /// Jimaku section to collect entries from
+    #[arg(long, value_enum, default_value_t = Section::All)]
+    section: Section,
+
+    #[arg(long, default_value = "https://jimaku.cc")]
+    jimaku_url: String,
+
+    /// Where to get jimaku entries and their files from
+    #[arg(long, value_enum, default_value_t = JimakuSource::Page)]
+    jimaku_source: JimakuSource,
+
+    /// Key for the jimaku API
+    #[arg(long, env = jimaku::api::API_KEY_VAR, hide_env_values = true)]
+    jimaku_api_key: Option<String>,
+
+    #[arg(long, default_value = "https://anilist.co")]
+    anilist_url: String,
+
+    #[arg(long, default_value = anilist::api::URL)]
+    anilist_api_url: String,
+
+    /// Where to get anilist entries from
+    #[arg(long, value_enum, default_value_t = AnilistSource::Page)]
+    anilist_source: AnilistSource,
+
+    /// Anilist ids to query in one API request
+    #[arg(long, default_value_t = anilist::api::MAX_PER_PAGE)]
+    anilist_batch_size: usize,
+
+    #[arg(short, long, default_value = "./data/data.tsv")]
+    output: PathBuf,
+
+    /// Value written for what is not known, which convert has to be given
+    /// too when it is not the default
+    #[arg(long, default_value = storage::MISSING_MARKER)]
+    missing_marker: String,
+
+    /// Skip entries already saved to the output (default)
+    #[arg(long, overrides_with_all = ["restart", "incremental"])]
+    resume: bool,
+
+    /// Discard the output and crawl every entry again
+    #[arg(long, overrides_with_all = ["resume", "incremental"])]
+    restart: bool,
+
+    /// Crawl saved entries again when they changed on the listing, and mark
+    /// the ones no longer listed as removed when every section is listed
+    /// without --listing-url. Pages are fetched again even
+    /// when they are in the cache
+    #[arg(long, overrides_with_all = ["resume", "restart"])]
+    incremental: bool,
+
+    /// Entries to fetch at the same time
+    #[arg(long, default_value_t = 4)]
+    concurrency: usize,
+
+    /// Stop after this many entries were saved
+    #[arg(long)]
+    max_entries: Option<usize>,
+
+    /// Abort after this many entries failed in a row for a reason that may
+    /// be temporary, like a timeout
+    #[arg(long, default_value_t = 5)]
+    max_failures_in_a_row: u32,
+
+    /// Rounds of trying again the entries that failed for a temporary reason
+    #[arg(long, default_value_t = 2)]
+    entry_retries: u32,
+
+    /// Retries for a failed HTTP request before giving up
+    #[arg(long, default_value_t = 10)]
+    request_retries: u64,
+
+    /// Backoff before the first retry, doubled for every next one
+    #[arg(long, default_value_t = 500)]
+    retry_base_delay_ms: u64,
+
+    /// Longest backoff between retries
+    #[arg(long, default_value_t = 60_000)]
+    retry_max_delay_ms: u64,
+
+    /// Requests per second allowed for a host and its subdomains
+    #[arg(
+        long = "rate-limit",
+        value_name = "HOST=RPS",
+        value_parser = parse_rate_limit,
+        default_values = ["jimaku.cc=1", "anilist.co=0.5"],
+    )]
+    rate_limits: Vec<(String, f64)>,
+
+    /// Requests per second allowed for other hosts
+    #[arg(long, default_value_t = 1.0, value_parser = parse_rate)]
+    default_rate_limit: f64,
+
+    /// Do not look up studio and staff names for the studio table
+    #[arg(long)]
+    no_name_lookup: bool,
+
+    /// Write studio and staff names instead of ids to the company columns
+    #[arg(long, conflicts_with = "no_name_lookup")]
+    company_names: bool,
+
+    /// Retries in a fresh tab for a rendered page before giving up
+    #[arg(long, default_value_t = 10)]
+    browser_retries: u32,
+
+    /// Browser tabs open at the same time, defaults to the concurrency
+    #[arg(long)]
+    browser_tabs: Option<usize>,
+
+    /// Restart the browser after this many page loads failed in a row
+    #[arg(long, default_value_t = 3)]
+    browser_max_failures_in_a_row: u32,
+
+    /// Time a tab gets for each browser call before it counts as hung
+    #[arg(long, default_value_t = 30)]
+    page_timeout_secs: u64,
+
+    /// Time an HTTP request gets for each attempt before it counts as timed
+    /// out
+    #[arg(long, default_value_t = 60)]
+    request_timeout_secs: u64,
+
+    /// Time an HTTP request gets to connect to the server
+    #[arg(long, default_value_t = 10)]
+    connect_timeout_secs: u64,
+
+    /// Directory for the raw page cache, caching is disabled without it.
+    /// Listings are always fetched again, and only served from the cache by
+    /// --offline
+    #[arg(long)]
+    cache_dir: Option<PathBuf>,
+
+    /// Rebuild the output purely from the page cache
+    #[arg(long, requires = "cache_dir")]
+    offline: bool,
+
+    /// Download the subtitle files of each entry for the content columns
+    #[arg(long)]
+    download_subtitles: bool,
+
+    /// Download zip and 7z batches to list the files inside them
+    #[arg(long)]
+    open_archives: bool,
+}
 
-        save_to_tsv(&tsv_entry, "./data/data.tsv")?;
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum Section {
+    Anime,
+    Dramas,
+    All,
+}
 
-        if current % 10 == 0 {
-            println!("{current}");
+impl Section {
+    fn paths(self) -> &'static [&'static str] {
+        match self {
+            Section::Anime => &[""],
+            Section::Dramas => &["/dramas"],
+            Section::All => &["", "/dramas"],
         }
-        current += 1;
     }
 
-    Ok(())
+    /// API searches that list the same entries as the section pages.
+    fn api_paths(self) -> Vec<String> {
+        match self {
+            Section::Anime => vec![jimaku::api::search_path(true)],
+            Section::Dramas => vec![jimaku::api::search_path(false)],
+            Section::All => vec![
+                jimaku::api::search_path(true),
+                jimaku::api::search_path(false),
+            ],
+        }
+    }
 }
 
-fn get_new_browser() -> Result<Browser> {
-    let launch_options = LaunchOptionsBuilder::default()
-        .build()
-        .map_err(|e| anyhow!("Failed to build launch options: {:?}", e))?;
-
-    Browser::new(launch_options).map_err(|e| anyhow!("Failed to create browser: {:?}", e))
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum JimakuSource {
+    /// Scrape the listing and entry pages
+    Page,
+    /// Query the JSON API, which needs an API key
+    Api,
 }
 
-async fn get_anilist_entry(
-    browser: &mut Browser,
-    anilist_id: i32,
-) -> Result<anilist::entry::Entry> {
-    const URL: &str = "https://anilist.co/anime/";
-    let url = URL.to_owned() + &anilist_id.to_string();
-
-    let (head, body) = get_page_data_chrome_with_retry(browser, &url).await?;
-
-    let anilist_entry =
-        parse_anilist_entry(&head, &body).context(format!("Failed to parse request head"))?;
+impl From<JimakuSource> for crawl::JimakuSource {
+    fn from(source: JimakuSource) -> Self {
+        match source {
+            JimakuSource::Page => crawl::JimakuSource::Page,
+            JimakuSource::Api => crawl::JimakuSource::Api,
+        }
+    }
+}
 
-    Ok(anilist_entry)
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum AnilistSource {
+    /// Render the anime page in the browser and scrape it
+    Page,
+    /// Query the GraphQL API
+    Api,
 }
 
-async fn get_page_data_chrome_with_retry(
-    browser: &mut Browser,
-    url: &str,
-) -> Result<(String, String)> {
-    for _ in 0..10 {
-        match get_page_data_chrome(&browser, url).await {
-            Ok((head, body)) => return Ok((head, body)),
-            Err(_) => {
-                eprintln!("restarting browser");
-                *browser = get_new_browser()?;
-            }
+impl From<AnilistSource> for crawl::AnilistSource {
+    fn from(source: AnilistSource) -> Self {
+        match source {
+            AnilistSource::Page => crawl::AnilistSource::Page,
+            AnilistSource::Api => crawl::AnilistSource::Api,
         }
     }
-
-    get_page_data_chrome(browser, url)
-        .await
-        .map_err(|e| anyhow!("Failed to get request head: {:?}", e))
 }
 
-async fn get_jimaku_entry_files_data(entry: &jimaku::entry::Entry) -> Result<Vec<FileData>> {
-    const URL: &str = "https://jimaku.cc/entry/";
-    let url = URL.to_owned() + &entry.id.to_string();
+impl CrawlArgs {
+    fn into_config(self) -> crawl::Config {
+        let complete_listing = self.listing_urls.is_empty() && matches!(self.section, Section::All);
+
+        let listing_urls = if self.listing_urls.is_empty() {
+            let paths = match self.jimaku_source {
+                JimakuSource::Page => self
+                    .section
+                    .paths()
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect(),
+                JimakuSource::Api => self.section.api_paths(),
+            };
+
+            paths
+                .iter()
+                .map(|path| format!("{}{path}", self.jimaku_url))
+                .collect()
+        } else {
+            self.listing_urls
+        };
 
-    let body = get_body(&url)
-        .await
-        .map_err(|e| anyhow!("Failed to get request body: {:?}", e))?;
+        crawl::Config {
+            listing_urls,
+            complete_listing,
+            jimaku_url: self.jimaku_url,
+            jimaku_source: self.jimaku_source.into(),
+            jimaku_api_key: self.jimaku_api_key,
+            anilist_url: self.anilist_url,
+            anilist_api_url: self.anilist_api_url,
+            anilist_source: self.anilist_source.into(),
+            anilist_batch_size: self.anilist_batch_size,
+            concurrency: self.concurrency,
+            output_path: self.output,
+            missing_marker: self.missing_marker,
+            mode: if self.restart {
+                crawl::Mode::Restart
+            } else if self.incremental {
+                crawl::Mode::Incremental
+            } else {
+                crawl::Mode::Resume
+            },
+            max_entries: self.max_entries,
+            max_failures_in_a_row: self.max_failures_in_a_row,
+            entry_retries: self.entry_retries,
+            request_retries: self.request_retries,
+            retry_base_delay: Duration::from_millis(self.retry_base_delay_ms),
+            retry_max_delay: Duration::from_millis(self.retry_max_delay_ms),
+            rate_limits: self.rate_limits.into_iter().collect(),
+            default_rate_limit: self.default_rate_limit,
+            name_lookup: !self.no_name_lookup,
+            company_names: self.company_names,
+            browser_retries: self.browser_retries,
+            browser_tabs: self.browser_tabs.unwrap_or(self.concurrency),
+            browser_max_failures_in_a_row: self.browser_max_failures_in_a_row,
+            page_timeout: Duration::from_secs(self.page_timeout_secs),
+            request_timeout: Duration::from_secs(self.request_timeout_secs),
+            connect_timeout: Duration::from_secs(self.connect_timeout_secs),
+            cache_dir: self.cache_dir,
+            offline: self.offline,
+            download_subtitles: self.download_subtitles,
+            open_archives: self.open_archives,
+        }
+    }
+}
 
-    let files_data = parse_files_data(&body).context("Failed to parse request body")?;
+fn parse_rate_limit(value: &str) -> Result<(String, f64), String> {
+    let (host, rate) = value
+        .split_once('=')
+        .ok_or_else(|| format!("expected HOST=RPS, got {value}"))?;
 
-    Ok(files_data)
+    let rate = parse_rate(rate).map_err(|err| format!("{err} for {host}"))?;
+
+    Ok((host.to_string(), rate))
 }
 
-async fn get_jimaku_entries(urls: &[&str]) -> Result<Vec<jimaku::entry::Entry>> {
-    let tasks: Vec<_> = urls
-        .iter()
-        .map(|&url| {
-            let url = url.to_string();
+fn parse_rate(value: &str) -> Result<f64, String> {
+    let rate = value
+        .parse::<f64>()
+        .map_err(|err| format!("invalid rate {value}: {err}"))?;
 
-            tokio::spawn(async move {
-                let body = get_body(&url)
-                    .await
-                    .map_err(|e| anyhow!("Failed to get request body: {:?}", e))?;
+    if !rate.is_finite() || rate < rate_limit::MIN_RATE {
+        return Err(format!(
+            "rate {value} must be a number of at least {}",
+            rate_limit::MIN_RATE
+        ));
+    }
 
-                let entries = parse_entries(&body).context("Failed to parse request body")?;
+    Ok(rate)
+}
 
-                let len = entries.len();
-                println!("{len}");
+#[tokio::main]
+async fn main() -> Result<()> {
+    let cli = Cli::parse();
 
-                Ok::<Vec<jimaku::entry::Entry>, anyhow::Error>(entries)
-            })
-        })
-        .collect();
+    match cli.command {
+        Command::Crawl(args) => crawl::run(&args.into_config()).await,
+        Command::Convert(args) => tsv_to_arff(&args.input, &args.output, &args.options()),
+        Command::Normalize { input, output } => {
+            let content = fs::read_to_string(&input)
+                .context(format!("Failed to read {}", input.display()))?;
 
-    let mut all_entries = Vec::new();
+            ARFFData::from_arff(&content).to_csv_normalized(&output.to_string_lossy());
 
-    for task in tasks {
-        match task.await {
-            Ok(entries) => all_entries.extend(entries?),
-            Err(err) => bail!("Task failed: {:?}", err),
+            Ok(())
         }
     }
-
-    Ok(all_entries)
 }
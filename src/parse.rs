@@ -1,3 +1,5 @@
 pub mod anilist;
+pub mod archive;
 pub mod arff;
 pub mod jimaku;
+pub mod subtitle;
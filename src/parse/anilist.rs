@@ -1,5 +1,12 @@
+pub mod api;
+pub mod date;
 pub mod entry;
+pub mod error;
 pub mod format;
 pub mod genre;
+pub mod relation;
+pub mod season;
 pub mod source;
+pub mod stats;
 pub mod status;
+pub mod tag;
@@ -0,0 +1,506 @@
+use std::collections::HashMap;
+
+use anyhow::{bail, Context, Result};
+use serde::Deserialize;
+use serde_json::json;
+
+use super::{
+    date::PartialDate,
+    entry::Entry,
+    error::ParseError,
+    format::api_to_format,
+    genre::to_genres,
+    relation::{api_to_relation_type, Relation},
+    season::api_to_season,
+    source::api_to_source,
+    stats::{Rankings, ScoreDistribution, StatusDistribution},
+    status::api_to_status,
+    tag::Tag,
+};
+use crate::fetch::Fetcher;
+
+pub const URL: &str = "https://graphql.anilist.co";
+
+/// Largest page the API returns.
+pub const MAX_PER_PAGE: usize = 50;
+
+/// Fields of `Media` that make up an [`Entry`].
+const MEDIA_FIELDS: &str = "
+    id
+    format
+    status
+    source
+    genres
+    episodes
+    duration
+    startDate { year month day }
+    endDate { year month day }
+    season
+    seasonYear
+    countryOfOrigin
+    isLicensed
+    averageScore
+    meanScore
+    popularity
+    favourites
+    rankings { rank type allTime }
+    stats { scoreDistribution { score amount } statusDistribution { status amount } }
+    nextAiringEpisode { episode }
+    studios { edges { node { id isAnimationStudio } } }
+    staff(sort: RELEVANCE, perPage: 25) { edges { role node { id } } }
+    tags { name category rank }
+    relations { edges { relationType node { id type } } }
+";
+
+#[derive(Debug, Deserialize)]
+struct Response<T> {
+    data: Option<T>,
+    #[serde(default)]
+    errors: Vec<ResponseError>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ResponseError {
+    message: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct PageData {
+    page: Page,
+}
+
+#[derive(Debug, Deserialize)]
+struct Page {
+    media: Vec<Media>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct StudioPageData {
+    page: StudioPage,
+}
+
+#[derive(Debug, Deserialize)]
+struct StudioPage {
+    studios: Vec<NamedStudio>,
+}
+
+#[derive(Debug, Deserialize)]
+struct NamedStudio {
+    id: i32,
+    name: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct StaffPageData {
+    page: StaffPage,
+}
+
+#[derive(Debug, Deserialize)]
+struct StaffPage {
+    staff: Vec<NamedStaff>,
+}
+
+#[derive(Debug, Deserialize)]
+struct NamedStaff {
+    id: i32,
+    name: StaffName,
+}
+
+#[derive(Debug, Deserialize)]
+struct StaffName {
+    full: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Media {
+    id: i32,
+    format: Option<String>,
+    status: Option<String>,
+    source: Option<String>,
+    #[serde(default)]
+    genres: Vec<String>,
+    episodes: Option<i32>,
+    duration: Option<i32>,
+    start_date: Option<FuzzyDate>,
+    end_date: Option<FuzzyDate>,
+    season: Option<String>,
+    season_year: Option<i32>,
+    country_of_origin: Option<String>,
+    is_licensed: Option<bool>,
+    average_score: Option<i32>,
+    mean_score: Option<i32>,
+    popularity: Option<i32>,
+    favourites: Option<i32>,
+    #[serde(default)]
+    rankings: Vec<Ranking>,
+    stats: Option<Stats>,
+    next_airing_episode: Option<AiringEpisode>,
+    studios: Option<Connection<Studio>>,
+    staff: Option<Connection<Staff>>,
+    #[serde(default)]
+    tags: Vec<MediaTag>,
+    relations: Option<Connection<RelatedMedia>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RelatedMedia {
+    id: i32,
+    #[serde(rename = "type")]
+    media_type: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct MediaTag {
+    name: String,
+    category: Option<String>,
+    rank: Option<i32>,
+}
+
+#[derive(Debug, Deserialize)]
+struct FuzzyDate {
+    year: Option<i32>,
+    month: Option<u32>,
+    day: Option<u32>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Ranking {
+    rank: i32,
+    #[serde(rename = "type")]
+    kind: String,
+    all_time: Option<bool>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Stats {
+    score_distribution: Option<Vec<ScoreAmount>>,
+    status_distribution: Option<Vec<StatusAmount>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ScoreAmount {
+    score: i32,
+    amount: i32,
+}
+
+#[derive(Debug, Deserialize)]
+struct StatusAmount {
+    status: String,
+    amount: i32,
+}
+
+#[derive(Debug, Deserialize)]
+struct AiringEpisode {
+    episode: i32,
+}
+
+#[derive(Debug, Deserialize)]
+struct Connection<T> {
+    edges: Vec<Edge<T>>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Edge<T> {
+    role: Option<String>,
+    relation_type: Option<String>,
+    node: T,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Studio {
+    id: i32,
+    is_animation_studio: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct Staff {
+    id: i32,
+}
+
+/// Fetches the anime with the given id from the GraphQL API at `url`.
+pub async fn get_anilist_entry<F: Fetcher>(fetcher: &F, url: &str, id: i32) -> Result<Entry> {
+    get_anilist_entries(fetcher, url, &[id])
+        .await?
+        .remove(&id)
+        .ok_or(ParseError::NotFound(id))?
+}
+
+/// Fetches the anime with the given ids from the GraphQL API at `url`, asking
+/// for up to [`MAX_PER_PAGE`] of them per request.
+///
+/// An entry that fails to convert only fails its own id, and ids the API does
+/// not know are reported as not found.
+pub async fn get_anilist_entries<F: Fetcher>(
+    fetcher: &F,
+    url: &str,
+    ids: &[i32],
+) -> Result<HashMap<i32, Result<Entry>>> {
+    let query = format!(
+        "query ($ids: [Int]) {{ Page(perPage: {MAX_PER_PAGE}) {{ \
+         media(id_in: $ids, type: ANIME) {{ {MEDIA_FIELDS} }} }} }}"
+    );
+
+    let mut entries = HashMap::new();
+
+    for chunk in ids.chunks(MAX_PER_PAGE) {
+        let data: PageData = post_query(fetcher, url, &query, chunk).await?;
+
+        for media in data.page.media {
+            let id = media.id;
+            let entry = to_entry(media).context(format!("Failed to convert anime {id}"));
+
+            entries.insert(id, entry);
+        }
+
+        for &id in chunk {
+            entries
+                .entry(id)
+                .or_insert_with(|| Err(ParseError::NotFound(id).into()));
+        }
+    }
+
+    Ok(entries)
+}
+
+/// Fetches the names of the studios with the given ids.
+pub async fn get_studio_names<F: Fetcher>(
+    fetcher: &F,
+    url: &str,
+    ids: &[i32],
+) -> Result<HashMap<i32, String>> {
+    let query = format!(
+        "query ($ids: [Int]) {{ Page(perPage: {MAX_PER_PAGE}) {{ \
+         studios(id_in: $ids) {{ id name }} }} }}"
+    );
+
+    let mut names = HashMap::new();
+
+    for chunk in ids.chunks(MAX_PER_PAGE) {
+        let data: StudioPageData = post_query(fetcher, url, &query, chunk).await?;
+
+        names.extend(
+            data.page
+                .studios
+                .into_iter()
+                .map(|studio| (studio.id, studio.name)),
+        );
+    }
+
+    Ok(names)
+}
+
+/// Fetches the full names of the staff with the given ids.
+pub async fn get_staff_names<F: Fetcher>(
+    fetcher: &F,
+    url: &str,
+    ids: &[i32],
+) -> Result<HashMap<i32, String>> {
+    let query = format!(
+        "query ($ids: [Int]) {{ Page(perPage: {MAX_PER_PAGE}) {{ \
+         staff(id_in: $ids) {{ id name {{ full }} }} }} }}"
+    );
+
+    let mut names = HashMap::new();
+
+    for chunk in ids.chunks(MAX_PER_PAGE) {
+        let data: StaffPageData = post_query(fetcher, url, &query, chunk).await?;
+
+        names.extend(
+            data.page
+                .staff
+                .into_iter()
+                .filter_map(|staff| Some((staff.id, staff.name.full?))),
+        );
+    }
+
+    Ok(names)
+}
+
+async fn post_query<F: Fetcher, T: for<'de> Deserialize<'de>>(
+    fetcher: &F,
+    url: &str,
+    query: &str,
+    ids: &[i32],
+) -> Result<T> {
+    let payload = json!({ "query": query, "variables": { "ids": ids } }).to_string();
+
+    let response = fetcher
+        .post_json(url, &payload)
+        .await
+        .context("Failed to post query")?;
+
+    parse_response(&response)
+}
+
+fn parse_response<T: for<'de> Deserialize<'de>>(response: &str) -> Result<T> {
+    let response: Response<T> =
+        serde_json::from_str(response).map_err(|source| ParseError::InvalidJson {
+            what: "API response",
+            source,
+        })?;
+
+    if !response.errors.is_empty() {
+        let messages: Vec<_> = response
+            .errors
+            .iter()
+            .map(|error| error.message.as_str())
+            .collect();
+
+        bail!(ParseError::Api(messages.join("; ")));
+    }
+
+    Ok(response
+        .data
+        .ok_or_else(|| ParseError::Api("no data".to_string()))?)
+}
+
+fn to_entry(media: Media) -> Result<Entry> {
+    let format = media
+        .format
+        .as_deref()
+        .map(api_to_format)
+        .ok_or(ParseError::MissingField("format"))?;
+
+    let status = media
+        .status
+        .as_deref()
+        .map(api_to_status)
+        .ok_or(ParseError::MissingField("status"))?;
+
+    // same as the page, where the sidebar shows the next airing episode
+    let episodes_amount = match media.next_airing_episode {
+        Some(airing) => Some(airing.episode),
+        None => media.episodes,
+    };
+
+    // anilist has no average score for an anime too few users scored
+    let rating_value = media.average_score;
+
+    let media_stats = media.stats.as_ref();
+    let score_distribution = media_stats
+        .and_then(|stats| stats.score_distribution.as_deref())
+        .map(to_score_distribution);
+    let status_distribution = media_stats
+        .and_then(|stats| stats.status_distribution.as_deref())
+        .map(to_status_distribution);
+
+    let rating_count = score_distribution.as_ref().map(ScoreDistribution::total);
+
+    let mut rankings = Rankings::default();
+
+    for ranking in media
+        .rankings
+        .iter()
+        .filter(|ranking| ranking.all_time == Some(true))
+    {
+        match ranking.kind.as_str() {
+            "RATED" => rankings.rated = Some(ranking.rank),
+            "POPULAR" => rankings.popular = Some(ranking.rank),
+            _ => {}
+        }
+    }
+
+    let studios = media
+        .studios
+        .map(|studios| studios.edges)
+        .unwrap_or_default();
+
+    let (production_companies, producers): (Vec<_>, Vec<_>) = studios
+        .iter()
+        .partition(|edge| edge.node.is_animation_studio);
+
+    let creators = media
+        .staff
+        .map(|staff| staff.edges)
+        .unwrap_or_default()
+        .iter()
+        .filter(|edge| edge.role.as_deref().is_some_and(is_creator_role))
+        .map(|edge| edge.node.id)
+        .collect();
+
+    Ok(Entry {
+        format,
+        status,
+        source: media.source.as_deref().map(api_to_source),
+        genres: to_genres(&media.genres),
+        episodes_amount,
+        time_required: media.duration.map(|minutes| format!("PT{minutes}M")),
+        start_date: media.start_date.as_ref().and_then(to_date),
+        end_date: media.end_date.as_ref().and_then(to_date),
+        season: media.season.as_deref().map(api_to_season),
+        season_year: media.season_year,
+        country_of_origin: media.country_of_origin,
+        is_licensed: media.is_licensed,
+        rating_value,
+        rating_count,
+        popularity: media.popularity,
+        favourites: media.favourites,
+        mean_score: media.mean_score,
+        rankings,
+        score_distribution,
+        status_distribution,
+        production_companies: production_companies
+            .iter()
+            .map(|edge| edge.node.id)
+            .collect(),
+        producers: producers.iter().map(|edge| edge.node.id).collect(),
+        creators,
+        tags: media
+            .tags
+            .into_iter()
+            .map(|tag| Tag {
+                name: tag.name,
+                category: tag.category,
+                rank: tag.rank.unwrap_or_default(),
+            })
+            .collect(),
+        relations: to_relations(media.relations),
+    })
+}
+
+fn to_score_distribution(scores: &[ScoreAmount]) -> ScoreDistribution {
+    let mut distribution = ScoreDistribution::default();
+    for score in scores {
+        distribution.add(score.score, score.amount);
+    }
+    distribution
+}
+
+fn to_status_distribution(statuses: &[StatusAmount]) -> StatusDistribution {
+    let mut distribution = StatusDistribution::default();
+    for status in statuses {
+        distribution.add(&status.status, status.amount);
+    }
+    distribution
+}
+
+fn to_relations(relations: Option<Connection<RelatedMedia>>) -> Vec<Relation> {
+    relations
+        .map(|relations| relations.edges)
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|edge| {
+            Some(Relation {
+                relation_type: api_to_relation_type(edge.relation_type.as_deref()?),
+                target_id: edge.node.id,
+                target_type: edge.node.media_type?.to_lowercase(),
+            })
+        })
+        .collect()
+}
+
+fn is_creator_role(role: &str) -> bool {
+    role.starts_with("Original Creator") || role.starts_with("Original Story")
+}
+
+fn to_date(date: &FuzzyDate) -> Option<PartialDate> {
+    PartialDate::new(date.year?, date.month, date.day)
+}
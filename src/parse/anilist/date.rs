@@ -0,0 +1,127 @@
+use std::fmt;
+
+use chrono::{Datelike, NaiveDate};
+
+/// How much of a date anilist knows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum DatePrecision {
+    Year,
+    Month,
+    Day,
+}
+
+/// A date that may only be known to the year or month, like the start date
+/// of an announced anime.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PartialDate {
+    first_day: NaiveDate,
+    precision: DatePrecision,
+}
+
+impl PartialDate {
+    /// Builds the date if its parts exist, dropping the day when the month is
+    /// not known.
+    #[must_use]
+    pub fn new(year: i32, month: Option<u32>, day: Option<u32>) -> Option<Self> {
+        let day = month.and(day);
+
+        let precision = match (month, day) {
+            (Some(_), Some(_)) => DatePrecision::Day,
+            (Some(_), None) => DatePrecision::Month,
+            _ => DatePrecision::Year,
+        };
+
+        Some(PartialDate {
+            first_day: NaiveDate::from_ymd_opt(year, month.unwrap_or(1), day.unwrap_or(1))?,
+            precision,
+        })
+    }
+
+    /// Parses a date like "2020", "2020-04" or "2020-04-01", as in the
+    /// JSON-LD of the page.
+    #[must_use]
+    pub fn parse(date: &str) -> Option<Self> {
+        let mut parts = date.trim().splitn(3, '-');
+
+        let year = parts.next()?.parse().ok()?;
+        let month = parts.next().map(str::parse).transpose().ok()?;
+        let day = parts.next().map(str::parse).transpose().ok()?;
+
+        PartialDate::new(year, month, day)
+    }
+
+    /// Parses a date like "2020", "Apr 2020" or "Apr 1, 2020", as in the
+    /// sidebar of the page.
+    #[must_use]
+    pub fn parse_display(date: &str) -> Option<Self> {
+        let parts: Vec<_> = date
+            .split(|letter: char| letter.is_whitespace() || letter == ',')
+            .filter(|part| !part.is_empty())
+            .collect();
+
+        match parts.as_slice() {
+            [year] => PartialDate::new(year.parse().ok()?, None, None),
+            [month, year] => PartialDate::new(year.parse().ok()?, Some(to_month(month)?), None),
+            [month, day, year] => PartialDate::new(
+                year.parse().ok()?,
+                Some(to_month(month)?),
+                Some(day.parse().ok()?),
+            ),
+            _ => None,
+        }
+    }
+
+    #[must_use]
+    pub fn precision(&self) -> DatePrecision {
+        self.precision
+    }
+
+    /// The first day the date can be.
+    #[must_use]
+    pub fn first_day(&self) -> NaiveDate {
+        self.first_day
+    }
+}
+
+impl fmt::Display for PartialDate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let date = self.first_day;
+
+        match self.precision {
+            DatePrecision::Year => write!(f, "{:04}", date.year()),
+            DatePrecision::Month => write!(f, "{:04}-{:02}", date.year(), date.month()),
+            DatePrecision::Day => write!(f, "{date}"),
+        }
+    }
+}
+
+impl fmt::Display for DatePrecision {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let precision = match self {
+            DatePrecision::Year => "year",
+            DatePrecision::Month => "month",
+            DatePrecision::Day => "day",
+        };
+        write!(f, "{precision}")
+    }
+}
+
+fn to_month(month: &str) -> Option<u32> {
+    let month = match month {
+        "Jan" => 1,
+        "Feb" => 2,
+        "Mar" => 3,
+        "Apr" => 4,
+        "May" => 5,
+        "Jun" => 6,
+        "Jul" => 7,
+        "Aug" => 8,
+        "Sep" => 9,
+        "Oct" => 10,
+        "Nov" => 11,
+        "Dec" => 12,
+        _ => return None,
+    };
+
+    Some(month)
+}
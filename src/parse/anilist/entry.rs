@@ -3,13 +3,22 @@ use scraper::{Html, Selector};
 use serde_json::Value;
 
 use super::{
+    date::PartialDate,
+    error::ParseError,
     format::{to_format, Format},
     genre::{to_genres, Genre},
+    relation::{parse_body_relations, Relation, RelationType},
+    season::{to_season, Season},
     source::{to_source, Source},
+    stats::{
+        parse_amount, parse_body_rankings, parse_body_status_distribution, Rankings,
+        ScoreDistribution, StatusDistribution,
+    },
     status::{to_status, Status},
+    tag::{parse_body_tags, Tag},
 };
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Entry {
     pub format: Format,
     pub status: Status,
@@ -17,13 +26,66 @@ pub struct Entry {
     pub genres: Vec<Genre>,
     pub episodes_amount: Option<i32>,
     pub time_required: Option<String>,
-    pub start_date: Option<String>,
-    pub end_date: Option<String>,
-    pub rating_value: i32,
-    pub rating_count: i32,
-    pub production_company: Option<i32>,
-    pub producer: Option<i32>,
-    pub creator: Option<i32>,
+    pub start_date: Option<PartialDate>,
+    pub end_date: Option<PartialDate>,
+    pub season: Option<Season>,
+    pub season_year: Option<i32>,
+    /// Like "JP", only known from the API
+    pub country_of_origin: Option<String>,
+    /// Whether the anime is officially available in English, only known from
+    /// the API
+    pub is_licensed: Option<bool>,
+    /// Weighted average of the scores, `None` when the anime has too few
+    pub rating_value: Option<i32>,
+    /// Users that scored the anime, `None` when it is not known
+    pub rating_count: Option<i32>,
+    /// Users that have the anime in a list
+    pub popularity: Option<i32>,
+    pub favourites: Option<i32>,
+    /// Plain mean of the scores, unlike the weighted average in `rating_value`
+    pub mean_score: Option<i32>,
+    pub rankings: Rankings,
+    /// Only known from the API, the page draws it as a chart
+    pub score_distribution: Option<ScoreDistribution>,
+    pub status_distribution: Option<StatusDistribution>,
+    pub production_companies: Vec<i32>,
+    pub producers: Vec<i32>,
+    pub creators: Vec<i32>,
+    pub tags: Vec<Tag>,
+    pub relations: Vec<Relation>,
+}
+
+impl Entry {
+    /// Values anilist used that none of the enums know, by field.
+    #[must_use]
+    pub fn unknown_values(&self) -> Vec<(&'static str, &str)> {
+        let mut values = Vec::new();
+
+        if let Format::Unknown(format) = &self.format {
+            values.push(("format", format.as_str()));
+        }
+        if let Status::Unknown(status) = &self.status {
+            values.push(("status", status.as_str()));
+        }
+        if let Some(Source::Unknown(source)) = &self.source {
+            values.push(("source", source.as_str()));
+        }
+        if let Some(Season::Unknown(season)) = &self.season {
+            values.push(("season", season.as_str()));
+        }
+        for relation in &self.relations {
+            if let RelationType::Unknown(relation_type) = &relation.relation_type {
+                values.push(("relation", relation_type.as_str()));
+            }
+        }
+        for genre in &self.genres {
+            if let Genre::Unknown(genre) = genre {
+                values.push(("genre", genre.as_str()));
+            }
+        }
+
+        values
+    }
 }
 
 #[derive(Debug)]
@@ -34,10 +96,10 @@ struct PendingEntry {
     genres: Vec<Genre>,
     episodes_amount: Option<i32>,
     time_required: Option<String>,
-    start_date: Option<String>,
-    end_date: Option<String>,
-    rating_value: i32,
-    rating_count: i32,
+    start_date: Option<PartialDate>,
+    end_date: Option<PartialDate>,
+    rating_value: Option<i32>,
+    rating_count: Option<i32>,
     production_companies: Option<Vec<i32>>,
     producers: Option<Vec<i32>>,
     creators: Option<Vec<i32>>,
@@ -66,6 +128,16 @@ pub fn parse_anilist_entry(head_data: &str, body_data: &str) -> Result<Entry> {
         },
     };
 
+    // the JSON-LD leaves out dates anilist only knows in part
+    if entry.start_date.is_none() {
+        entry.start_date = parse_body_date(&body_document, "Start Date")?;
+    }
+    if entry.end_date.is_none() {
+        entry.end_date = parse_body_date(&body_document, "End Date")?;
+    }
+
+    let (season, season_year) = parse_body_season(&body_document)?;
+
     let airing_episodes_amount = parse_body_airing_episodes_amount(&body_document).ok();
 
     entry.episodes_amount = match airing_episodes_amount {
@@ -82,29 +154,23 @@ pub fn parse_anilist_entry(head_data: &str, body_data: &str) -> Result<Entry> {
         time_required: entry.time_required,
         start_date: entry.start_date,
         end_date: entry.end_date,
+        season,
+        season_year,
+        country_of_origin: None,
+        is_licensed: None,
         rating_value: entry.rating_value,
         rating_count: entry.rating_count,
-        production_company: match entry.production_companies {
-            Some(production_companies) => match production_companies.get(0) {
-                Some(&production_company) => Some(production_company),
-                None => None,
-            },
-            None => None,
-        },
-        creator: match entry.creators {
-            Some(creators) => match creators.get(0) {
-                Some(&creator) => Some(creator),
-                None => None,
-            },
-            None => None,
-        },
-        producer: match entry.producers {
-            Some(producers) => match producers.get(0) {
-                Some(&producer) => Some(producer),
-                None => None,
-            },
-            None => None,
-        },
+        popularity: parse_body_data_set_number(&body_document, "Popularity")?,
+        favourites: parse_body_data_set_number(&body_document, "Favorites")?,
+        mean_score: parse_body_data_set_number(&body_document, "Mean Score")?,
+        rankings: parse_body_rankings(&body_document)?,
+        score_distribution: None,
+        status_distribution: parse_body_status_distribution(&body_document)?,
+        production_companies: entry.production_companies.unwrap_or_default(),
+        producers: entry.producers.unwrap_or_default(),
+        creators: entry.creators.unwrap_or_default(),
+        tags: parse_body_tags(&body_document)?,
+        relations: parse_body_relations(&body_document)?,
     };
 
     Ok(result)
@@ -133,12 +199,12 @@ fn parse_body_format(body_document: &Html) -> Result<Format> {
                         .join("")
                         .trim()
                         .to_string();
-                    return to_format(&format_text);
+                    return Ok(to_format(&format_text));
                 }
             }
         }
     }
-    Err(anyhow!("Format field not found"))
+    Err(ParseError::MissingField("Format").into())
 }
 
 fn parse_body_source(body_document: &Html) -> Result<Source> {
@@ -164,12 +230,12 @@ fn parse_body_source(body_document: &Html) -> Result<Source> {
                         .join("")
                         .trim()
                         .to_string();
-                    return to_source(&source_text);
+                    return Ok(to_source(&source_text));
                 }
             }
         }
     }
-    Err(anyhow!("Source field not found"))
+    Err(ParseError::MissingField("Source").into())
 }
 
 fn parse_body_airing_episodes_amount(body_document: &Html) -> Result<i32> {
@@ -202,20 +268,107 @@ fn parse_body_airing_episodes_amount(body_document: &Html) -> Result<i32> {
                         if let Some(colon_pos) = ep_text.find(":") {
                             let episode_str = &ep_text[2..colon_pos].trim();
 
-                            let episode_count = episode_str
-                                .parse::<i32>()
-                                .context("Failed to parse episode count to i32")?;
+                            let episode_count = episode_str.parse::<i32>().map_err(|_| {
+                                ParseError::InvalidField {
+                                    field: "episode count",
+                                    value: episode_str.to_string(),
+                                }
+                            })?;
 
                             return Ok(episode_count);
                         }
                     }
 
-                    return Err(anyhow!("Failed to find 'Ep' pattern in Airing episodes"));
+                    return Err(ParseError::InvalidField {
+                        field: "Airing episodes",
+                        value: value_text,
+                    }
+                    .into());
                 }
             }
         }
     }
-    Err(anyhow!("Airing episodes amount field not found"))
+    Err(ParseError::MissingField("Airing episodes amount").into())
+}
+
+/// Reads the season of the sidebar, like "Spring 2020". Either part can be
+/// missing, and the row is left out when both are.
+fn parse_body_season(body_document: &Html) -> Result<(Option<Season>, Option<i32>)> {
+    let mut season = None;
+    let mut season_year = None;
+
+    if let Some(value_text) = parse_body_data_set_text(body_document, "Season")? {
+        for part in value_text.split_whitespace() {
+            match part.parse::<i32>() {
+                Ok(year) => season_year = Some(year),
+                Err(_) => season = Some(to_season(part)),
+            }
+        }
+    }
+
+    Ok((season, season_year))
+}
+
+/// Reads a date of the sidebar, like "Apr 1, 2020" or only "2026" for an
+/// announced anime.
+fn parse_body_date(body_document: &Html, name: &'static str) -> Result<Option<PartialDate>> {
+    let Some(value_text) = parse_body_data_set_text(body_document, name)? else {
+        return Ok(None);
+    };
+
+    PartialDate::parse_display(&value_text)
+        .map(Some)
+        .ok_or_else(|| {
+            ParseError::InvalidField {
+                field: name,
+                value: value_text,
+            }
+            .into()
+        })
+}
+
+/// Reads a number of the sidebar, like "Popularity" or "Mean Score".
+fn parse_body_data_set_number(body_document: &Html, name: &'static str) -> Result<Option<i32>> {
+    let Some(value_text) = parse_body_data_set_text(body_document, name)? else {
+        return Ok(None);
+    };
+
+    parse_amount(&value_text).map(Some).ok_or_else(|| {
+        ParseError::InvalidField {
+            field: name,
+            value: value_text,
+        }
+        .into()
+    })
+}
+
+/// Reads the value of the sidebar row with the name. The sidebar leaves out
+/// a row when anilist has no value for it.
+fn parse_body_data_set_text(body_document: &Html, name: &str) -> Result<Option<String>> {
+    let data_set_selector = Selector::parse("div.data-set")
+        .map_err(|err| anyhow!("Failed to parse selector: {:?}", err))?;
+    let type_selector = Selector::parse("div.type")
+        .map_err(|err| anyhow!("Failed to parse selector: {:?}", err))?;
+    let value_selector = Selector::parse("div.value")
+        .map_err(|err| anyhow!("Failed to parse selector: {:?}", err))?;
+
+    for data_set_element in body_document.select(&data_set_selector) {
+        let Some(type_element) = data_set_element.select(&type_selector).next() else {
+            continue;
+        };
+
+        if type_element.text().collect::<String>().trim() != name {
+            continue;
+        }
+
+        if let Some(value_element) = data_set_element.select(&value_selector).next() {
+            return Ok(Some(
+                value_element.text().collect::<String>().trim().to_string(),
+            ));
+        }
+    }
+
+    Ok(None)
 }
 
 fn parse_body_status(body_document: &Html) -> Result<Status> {
@@ -241,12 +394,12 @@ fn parse_body_status(body_document: &Html) -> Result<Status> {
                         .join("")
                         .trim()
                         .to_string();
-                    return to_status(&status_text);
+                    return Ok(to_status(&status_text));
                 }
             }
         }
     }
-    Err(anyhow!("Status field not found"))
+    Err(ParseError::MissingField("Status").into())
 }
 
 fn parse_head_data(head_data: &str) -> Result<PendingEntry> {
@@ -257,10 +410,14 @@ fn parse_head_data(head_data: &str) -> Result<PendingEntry> {
     let script_content = document
         .select(&script_selector)
         .next()
-        .context("Failed to find script tag")?
+        .ok_or(ParseError::MissingField("JSON-LD script"))?
         .inner_html();
 
-    let json_data: Value = serde_json::from_str(&script_content).context("Failed to parse JSON")?;
+    let json_data: Value =
+        serde_json::from_str(&script_content).map_err(|source| ParseError::InvalidJson {
+            what: "JSON-LD",
+            source,
+        })?;
 
     let main_entity = &json_data["mainEntity"];
 
@@ -270,70 +427,16 @@ fn parse_head_data(head_data: &str) -> Result<PendingEntry> {
 
     let time_required = main_entity["timeRequired"].as_str().map(|s| s.to_string());
 
-    let start_date = main_entity["startDate"].as_str().map(|s| s.to_string());
-    let end_date = main_entity["endDate"].as_str().map(|s| s.to_string());
-
-    let rating_value = i32::try_from(
-        main_entity["aggregateRating"]["ratingValue"]
-            .as_i64()
-            .context(format!("Failed to get ratingValue from {:?}", main_entity))?,
-    )
-    .context("Failed to convert ratingValue to i32")?;
+    let start_date = parse_head_date(main_entity, "startDate")?;
+    let end_date = parse_head_date(main_entity, "endDate")?;
 
-    let rating_count = i32::try_from(
-        main_entity["aggregateRating"]["ratingCount"]
-            .as_i64()
-            .context(format!("Failed to get ratingCount from {:?}", main_entity))?,
-    )
-    .context("Failed to convert ratingCount to i32")?;
+    // the page leaves out the rating of an anime too few users scored
+    let rating_value = parse_head_rating(main_entity, "ratingValue")?;
+    let rating_count = parse_head_rating(main_entity, "ratingCount")?;
 
-    let production_companies = main_entity["productionCompany"]
-        .as_array()
-        .map(|arr| {
-            arr.iter()
-                .map(|company| {
-                    company["@id"]
-                        .as_str()
-                        .context(format!(
-                            "Failed to get @id for productionCompany from {:?}",
-                            company
-                        ))
-                        .and_then(|id| extract_id_from_url(id))
-                })
-                .collect::<Result<Vec<_>>>()
-        })
-        .transpose()?;
-
-    let producers = main_entity["producer"]
-        .as_array()
-        .map(|arr| {
-            arr.iter()
-                .map(|producer| {
-                    producer["@id"]
-                        .as_str()
-                        .context(format!(
-                            "Failed to get @id for producer from {:?}",
-                            producer
-                        ))
-                        .and_then(|id| extract_id_from_url(id))
-                })
-                .collect::<Result<Vec<_>>>()
-        })
-        .transpose()?;
-
-    let creators = main_entity["creator"]
-        .as_array()
-        .map(|arr| {
-            arr.iter()
-                .map(|creator| {
-                    creator["@id"]
-                        .as_str()
-                        .context(format!("Failed to get @id for creator from {:?}", creator))
-                        .and_then(|id| extract_id_from_url(id))
-                })
-                .collect::<Result<Vec<_>>>()
-        })
-        .transpose()?;
+    let production_companies = parse_head_ids(main_entity, "productionCompany")?;
+    let producers = parse_head_ids(main_entity, "producer")?;
+    let creators = parse_head_ids(main_entity, "creator")?;
 
     let genres = main_entity["genre"]
         .as_array()
@@ -350,7 +453,7 @@ fn parse_head_data(head_data: &str) -> Result<PendingEntry> {
         .transpose()?
         .unwrap_or_default();
 
-    let genres = to_genres(&genres)?;
+    let genres = to_genres(&genres);
 
     let entry = PendingEntry {
         format: None,
@@ -371,6 +474,45 @@ fn parse_head_data(head_data: &str) -> Result<PendingEntry> {
     Ok(entry)
 }
 
+fn parse_head_rating(main_entity: &Value, name: &str) -> Result<Option<i32>> {
+    main_entity["aggregateRating"][name]
+        .as_i64()
+        .map(i32::try_from)
+        .transpose()
+        .context(format!("Failed to convert {name} to i32"))
+}
+
+/// Reads the anilist ids of the companies or staff listed under `name`.
+fn parse_head_ids(main_entity: &Value, name: &str) -> Result<Option<Vec<i32>>> {
+    main_entity[name]
+        .as_array()
+        .map(|arr| {
+            arr.iter()
+                .map(|item| {
+                    item["@id"]
+                        .as_str()
+                        .context(format!("Failed to get @id for {name} from {item:?}"))
+                        .and_then(extract_id_from_url)
+                })
+                .collect::<Result<Vec<_>>>()
+        })
+        .transpose()
+}
+
+fn parse_head_date(main_entity: &Value, name: &'static str) -> Result<Option<PartialDate>> {
+    let Some(date) = main_entity[name].as_str() else {
+        return Ok(None);
+    };
+
+    PartialDate::parse(date).map(Some).ok_or_else(|| {
+        ParseError::InvalidField {
+            field: name,
+            value: date.to_string(),
+        }
+        .into()
+    })
+}
+
 fn extract_id_from_url(url: &str) -> Result<i32> {
     let parts: Vec<&str> = url.split('/').collect();
     if let Some(id_part) = parts.iter().find(|&&part| part.parse::<i32>().is_ok()) {
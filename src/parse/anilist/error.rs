@@ -0,0 +1,45 @@
+use thiserror::Error;
+
+/// Why an anilist page or API response could not be turned into an entry.
+#[derive(Debug, Error)]
+pub enum ParseError {
+    #[error("{0} field not found")]
+    MissingField(&'static str),
+    #[error("{0} field not found on the rendered page")]
+    NotRendered(&'static str),
+    #[error("Invalid {field}: {value}")]
+    InvalidField { field: &'static str, value: String },
+    #[error("Failed to parse {what} JSON")]
+    InvalidJson {
+        what: &'static str,
+        #[source]
+        source: serde_json::Error,
+    },
+    #[error("Anime {0} not found")]
+    NotFound(i32),
+    #[error("API returned errors: {0}")]
+    Api(String),
+}
+
+impl ParseError {
+    /// Whether parsing the same anime again cannot help. API errors may be
+    /// an overloaded server and a rendered page may not have finished
+    /// loading, everything else is in the data itself.
+    #[must_use]
+    pub fn is_permanent(&self) -> bool {
+        !matches!(self, ParseError::Api(_) | ParseError::NotRendered(_))
+    }
+
+    /// Short name of the kind of failure, for the failure summary.
+    #[must_use]
+    pub fn category(&self) -> &'static str {
+        match self {
+            ParseError::MissingField(_) => "missing anilist field",
+            ParseError::NotRendered(_) => "anilist page not rendered",
+            ParseError::InvalidField { .. } => "invalid anilist field",
+            ParseError::InvalidJson { .. } => "invalid anilist JSON",
+            ParseError::NotFound(_) => "anime not found",
+            ParseError::Api(_) => "anilist API error",
+        }
+    }
+}
@@ -1,8 +1,6 @@
 use std::fmt;
 
-use anyhow::{bail, Result};
-
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Format {
     TvShow,
     Movie,
@@ -11,18 +9,45 @@ pub enum Format {
     Ova,
     Ona,
     Music,
+    Manga,
+    Novel,
+    OneShot,
+    /// A format anilist added since, kept as it was written
+    Unknown(String),
+}
+
+#[must_use]
+pub fn to_format(format: &str) -> Format {
+    match format {
+        "TV" => Format::TvShow,
+        "Movie" => Format::Movie,
+        "TV Short" => Format::TvShort,
+        "Special" => Format::Special,
+        "OVA" => Format::Ova,
+        "ONA" => Format::Ona,
+        "Music" => Format::Music,
+        "Manga" => Format::Manga,
+        "Novel" | "Light Novel" => Format::Novel,
+        "One Shot" => Format::OneShot,
+        _ => Format::Unknown(format.to_string()),
+    }
 }
 
-pub fn to_format(format: &str) -> Result<Format> {
+/// Maps the `MediaFormat` value of the GraphQL API.
+#[must_use]
+pub fn api_to_format(format: &str) -> Format {
     match format {
-        "TV" => Ok(Format::TvShow),
-        "Movie" => Ok(Format::Movie),
-        "TV Short" => Ok(Format::TvShort),
-        "Special" => Ok(Format::Special),
-        "OVA" => Ok(Format::Ova),
-        "ONA" => Ok(Format::Ona),
-        "Music" => Ok(Format::Music),
-        _ => bail!("Unknown format: {}", format),
+        "TV" => Format::TvShow,
+        "MOVIE" => Format::Movie,
+        "TV_SHORT" => Format::TvShort,
+        "SPECIAL" => Format::Special,
+        "OVA" => Format::Ova,
+        "ONA" => Format::Ona,
+        "MUSIC" => Format::Music,
+        "MANGA" => Format::Manga,
+        "NOVEL" => Format::Novel,
+        "ONE_SHOT" => Format::OneShot,
+        _ => Format::Unknown(format.to_string()),
     }
 }
 
@@ -36,6 +61,10 @@ impl fmt::Display for Format {
             Format::Ova => "OVA",
             Format::Ona => "ONA",
             Format::Music => "Music",
+            Format::Manga => "Manga",
+            Format::Novel => "Novel",
+            Format::OneShot => "One Shot",
+            Format::Unknown(format) => format,
         };
         write!(f, "{}", s)
     }
@@ -1,6 +1,4 @@
-use anyhow::{bail, Result};
-
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Genre {
     Action,
     Adventure,
@@ -20,31 +18,34 @@ pub enum Genre {
     Sports,
     Supernatural,
     Thriller,
+    /// A genre outside the fixed list, like "Hentai", kept as it was written
+    Unknown(String),
 }
 
-pub fn to_genres(genres: &Vec<String>) -> Result<Vec<Genre>> {
+#[must_use]
+pub fn to_genres(genres: &Vec<String>) -> Vec<Genre> {
     genres
         .into_iter()
         .map(|genre| match genre.as_str() {
-            "Action" => Ok(Genre::Action),
-            "Adventure" => Ok(Genre::Adventure),
-            "Comedy" => Ok(Genre::Comedy),
-            "Drama" => Ok(Genre::Drama),
-            "Ecchi" => Ok(Genre::Ecchi),
-            "Fantasy" => Ok(Genre::Fantasy),
-            "Horror" => Ok(Genre::Horror),
-            "Mahou Shoujo" => Ok(Genre::MahouShoujo),
-            "Mecha" => Ok(Genre::Mecha),
-            "Music" => Ok(Genre::Music),
-            "Mystery" => Ok(Genre::Mystery),
-            "Psychological" => Ok(Genre::Psychological),
-            "Romance" => Ok(Genre::Romance),
-            "Sci-Fi" => Ok(Genre::SciFi),
-            "Slice of Life" => Ok(Genre::SliceOfLife),
-            "Sports" => Ok(Genre::Sports),
-            "Supernatural" => Ok(Genre::Supernatural),
-            "Thriller" => Ok(Genre::Thriller),
-            _ => bail!("Unknown genre: {}", genre),
+            "Action" => Genre::Action,
+            "Adventure" => Genre::Adventure,
+            "Comedy" => Genre::Comedy,
+            "Drama" => Genre::Drama,
+            "Ecchi" => Genre::Ecchi,
+            "Fantasy" => Genre::Fantasy,
+            "Horror" => Genre::Horror,
+            "Mahou Shoujo" => Genre::MahouShoujo,
+            "Mecha" => Genre::Mecha,
+            "Music" => Genre::Music,
+            "Mystery" => Genre::Mystery,
+            "Psychological" => Genre::Psychological,
+            "Romance" => Genre::Romance,
+            "Sci-Fi" => Genre::SciFi,
+            "Slice of Life" => Genre::SliceOfLife,
+            "Sports" => Genre::Sports,
+            "Supernatural" => Genre::Supernatural,
+            "Thriller" => Genre::Thriller,
+            _ => Genre::Unknown(genre.clone()),
         })
         .collect()
 }
@@ -0,0 +1,147 @@
+use std::fmt;
+
+use anyhow::{anyhow, Result};
+use scraper::{Html, Selector};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RelationType {
+    Adaptation,
+    Prequel,
+    Sequel,
+    Parent,
+    SideStory,
+    Character,
+    Summary,
+    Alternative,
+    SpinOff,
+    Other,
+    Source,
+    Compilation,
+    Contains,
+    /// A relation anilist added since, kept as it was written
+    Unknown(String),
+}
+
+/// A link from an anime to another anime or manga of anilist.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Relation {
+    pub relation_type: RelationType,
+    pub target_id: i32,
+    /// "anime" or "manga"
+    pub target_type: String,
+}
+
+#[must_use]
+pub fn to_relation_type(relation_type: &str) -> RelationType {
+    match relation_type {
+        "Adaptation" => RelationType::Adaptation,
+        "Prequel" => RelationType::Prequel,
+        "Sequel" => RelationType::Sequel,
+        "Parent" => RelationType::Parent,
+        "Side Story" => RelationType::SideStory,
+        "Character" => RelationType::Character,
+        "Summary" => RelationType::Summary,
+        "Alternative" => RelationType::Alternative,
+        "Spin Off" => RelationType::SpinOff,
+        "Other" => RelationType::Other,
+        "Source" => RelationType::Source,
+        "Compilation" => RelationType::Compilation,
+        "Contains" => RelationType::Contains,
+        _ => RelationType::Unknown(relation_type.to_string()),
+    }
+}
+
+/// Maps the `MediaRelation` value of the GraphQL API.
+#[must_use]
+pub fn api_to_relation_type(relation_type: &str) -> RelationType {
+    match relation_type {
+        "ADAPTATION" => RelationType::Adaptation,
+        "PREQUEL" => RelationType::Prequel,
+        "SEQUEL" => RelationType::Sequel,
+        "PARENT" => RelationType::Parent,
+        "SIDE_STORY" => RelationType::SideStory,
+        "CHARACTER" => RelationType::Character,
+        "SUMMARY" => RelationType::Summary,
+        "ALTERNATIVE" => RelationType::Alternative,
+        "SPIN_OFF" => RelationType::SpinOff,
+        "OTHER" => RelationType::Other,
+        "SOURCE" => RelationType::Source,
+        "COMPILATION" => RelationType::Compilation,
+        "CONTAINS" => RelationType::Contains,
+        _ => RelationType::Unknown(relation_type.to_string()),
+    }
+}
+
+impl RelationType {
+    /// Whether the relation links two parts of the same story, unlike
+    /// `Character` or `Other`.
+    #[must_use]
+    pub fn is_franchise(&self) -> bool {
+        !matches!(
+            self,
+            RelationType::Character | RelationType::Other | RelationType::Unknown(_)
+        )
+    }
+}
+
+impl fmt::Display for RelationType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let relation_type = match self {
+            RelationType::Adaptation => "Adaptation",
+            RelationType::Prequel => "Prequel",
+            RelationType::Sequel => "Sequel",
+            RelationType::Parent => "Parent",
+            RelationType::SideStory => "Side Story",
+            RelationType::Character => "Character",
+            RelationType::Summary => "Summary",
+            RelationType::Alternative => "Alternative",
+            RelationType::SpinOff => "Spin Off",
+            RelationType::Other => "Other",
+            RelationType::Source => "Source",
+            RelationType::Compilation => "Compilation",
+            RelationType::Contains => "Contains",
+            RelationType::Unknown(relation_type) => relation_type,
+        };
+        write!(f, "{relation_type}")
+    }
+}
+
+/// Reads the relations of the overview, where every card links to the target
+/// like "/anime/123/Title" and names the relation in its header.
+pub fn parse_body_relations(body_document: &Html) -> Result<Vec<Relation>> {
+    let card_selector = Selector::parse("div.relations .media-preview-card")
+        .map_err(|err| anyhow!("Failed to parse selector: {:?}", err))?;
+    let cover_selector =
+        Selector::parse("a.cover").map_err(|err| anyhow!("Failed to parse selector: {:?}", err))?;
+    let header_selector = Selector::parse(".info-header")
+        .map_err(|err| anyhow!("Failed to parse selector: {:?}", err))?;
+
+    let relations = body_document
+        .select(&card_selector)
+        .filter_map(|card_element| {
+            let href = card_element
+                .select(&cover_selector)
+                .next()?
+                .value()
+                .attr("href")?;
+
+            let mut parts = href.split('/').filter(|part| !part.is_empty());
+            let target_type = parts.next()?.to_string();
+            let target_id = parts.next()?.parse().ok()?;
+
+            let relation_type = card_element
+                .select(&header_selector)
+                .next()?
+                .text()
+                .collect::<String>();
+
+            Some(Relation {
+                relation_type: to_relation_type(relation_type.trim()),
+                target_id,
+                target_type,
+            })
+        })
+        .collect();
+
+    Ok(relations)
+}
@@ -0,0 +1,47 @@
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Season {
+    Winter,
+    Spring,
+    Summer,
+    Fall,
+    /// A season anilist added since, kept as it was written
+    Unknown(String),
+}
+
+#[must_use]
+pub fn to_season(season: &str) -> Season {
+    match season {
+        "Winter" => Season::Winter,
+        "Spring" => Season::Spring,
+        "Summer" => Season::Summer,
+        "Fall" => Season::Fall,
+        _ => Season::Unknown(season.to_string()),
+    }
+}
+
+/// Maps the `MediaSeason` value of the GraphQL API.
+#[must_use]
+pub fn api_to_season(season: &str) -> Season {
+    match season {
+        "WINTER" => Season::Winter,
+        "SPRING" => Season::Spring,
+        "SUMMER" => Season::Summer,
+        "FALL" => Season::Fall,
+        _ => Season::Unknown(season.to_string()),
+    }
+}
+
+impl fmt::Display for Season {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let season = match self {
+            Season::Winter => "Winter",
+            Season::Spring => "Spring",
+            Season::Summer => "Summer",
+            Season::Fall => "Fall",
+            Season::Unknown(season) => season,
+        };
+        write!(f, "{season}")
+    }
+}
@@ -1,8 +1,6 @@
 use std::fmt;
 
-use anyhow::{bail, Result};
-
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Source {
     Original,
     Manga,
@@ -17,26 +15,54 @@ pub enum Source {
     LiveAction,
     Game,
     MultimediaProject,
+    PictureBook,
     Other,
+    /// A source anilist added since, kept as it was written
+    Unknown(String),
+}
+
+#[must_use]
+pub fn to_source(source: &str) -> Source {
+    match source {
+        "Original" => Source::Original,
+        "Manga" => Source::Manga,
+        "Light Novel" => Source::LightNovel,
+        "Web Novel" => Source::WebNovel,
+        "Novel" => Source::Novel,
+        "Anime" => Source::Anime,
+        "Visual Novel" => Source::VisualNovel,
+        "Video Game" => Source::VideoGame,
+        "Doujinshi" => Source::Doujinshi,
+        "Comic" => Source::Comic,
+        "Live Action" => Source::LiveAction,
+        "Game" => Source::Game,
+        "Multimedia Project" => Source::MultimediaProject,
+        "Picture Book" => Source::PictureBook,
+        "Other" => Source::Other,
+        _ => Source::Unknown(source.to_string()),
+    }
 }
 
-pub fn to_source(source: &str) -> Result<Source> {
+/// Maps the `MediaSource` value of the GraphQL API.
+#[must_use]
+pub fn api_to_source(source: &str) -> Source {
     match source {
-        "Original" => Ok(Source::Original),
-        "Manga" => Ok(Source::Manga),
-        "Light Novel" => Ok(Source::LightNovel),
-        "Web Novel" => Ok(Source::WebNovel),
-        "Novel" => Ok(Source::Novel),
-        "Anime" => Ok(Source::Anime),
-        "Visual Novel" => Ok(Source::VisualNovel),
-        "Video Game" => Ok(Source::VideoGame),
-        "Doujinshi" => Ok(Source::Doujinshi),
-        "Comic" => Ok(Source::Comic),
-        "Live Action" => Ok(Source::LiveAction),
-        "Game" => Ok(Source::Game),
-        "Multimedia Project" => Ok(Source::MultimediaProject),
-        "Other" => Ok(Source::Other),
-        _ => bail!("Unknown source: {}", source),
+        "ORIGINAL" => Source::Original,
+        "MANGA" => Source::Manga,
+        "LIGHT_NOVEL" => Source::LightNovel,
+        "WEB_NOVEL" => Source::WebNovel,
+        "NOVEL" => Source::Novel,
+        "ANIME" => Source::Anime,
+        "VISUAL_NOVEL" => Source::VisualNovel,
+        "VIDEO_GAME" => Source::VideoGame,
+        "DOUJINSHI" => Source::Doujinshi,
+        "COMIC" => Source::Comic,
+        "LIVE_ACTION" => Source::LiveAction,
+        "GAME" => Source::Game,
+        "MULTIMEDIA_PROJECT" => Source::MultimediaProject,
+        "PICTURE_BOOK" => Source::PictureBook,
+        "OTHER" => Source::Other,
+        _ => Source::Unknown(source.to_string()),
     }
 }
 
@@ -56,7 +82,9 @@ impl fmt::Display for Source {
             Source::LiveAction => "Live Action",
             Source::Game => "Game",
             Source::MultimediaProject => "Multimedia Project",
+            Source::PictureBook => "Picture Book",
             Source::Other => "Other",
+            Source::Unknown(source) => source,
         };
         write!(f, "{}", s)
     }
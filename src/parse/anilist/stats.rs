@@ -0,0 +1,149 @@
+use anyhow::{anyhow, Result};
+use scraper::{ElementRef, Html, Selector};
+
+/// Users that gave each score, from 10 to 100 in steps of 10.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ScoreDistribution(pub [i32; 10]);
+
+impl ScoreDistribution {
+    /// Adds the users of a score, ignoring scores that are not a step.
+    pub fn add(&mut self, score: i32, amount: i32) {
+        if score % 10 != 0 {
+            return;
+        }
+
+        if let Some(slot) = usize::try_from(score / 10 - 1)
+            .ok()
+            .and_then(|index| self.0.get_mut(index))
+        {
+            *slot += amount;
+        }
+    }
+
+    /// Users with the score, which is one of 10, 20, ..., 100.
+    #[must_use]
+    pub fn get(&self, score: usize) -> i32 {
+        self.0[score / 10 - 1]
+    }
+
+    #[must_use]
+    pub fn total(&self) -> i32 {
+        self.0.iter().sum()
+    }
+}
+
+/// Users that have the anime in each of their lists.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StatusDistribution {
+    pub current: i32,
+    pub planning: i32,
+    pub completed: i32,
+    pub dropped: i32,
+    pub paused: i32,
+    pub repeating: i32,
+}
+
+impl StatusDistribution {
+    /// Adds the users of a list, by the name the API or the page uses for it.
+    pub fn add(&mut self, status: &str, amount: i32) {
+        let slot = match status.trim().to_uppercase().as_str() {
+            "CURRENT" | "WATCHING" => &mut self.current,
+            "PLANNING" | "PLAN TO WATCH" => &mut self.planning,
+            "COMPLETED" => &mut self.completed,
+            "DROPPED" => &mut self.dropped,
+            "PAUSED" => &mut self.paused,
+            "REPEATING" | "REWATCHING" => &mut self.repeating,
+            _ => return,
+        };
+
+        *slot += amount;
+    }
+}
+
+/// All-time ranks of the anime among every anime on anilist.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Rankings {
+    pub rated: Option<i32>,
+    pub popular: Option<i32>,
+}
+
+/// Reads the all-time ranks of the sidebar, which shows each one as a link
+/// like "#123 Highest Rated All Time". Ranks of a year or season are skipped.
+pub fn parse_body_rankings(body_document: &Html) -> Result<Rankings> {
+    let ranking_selector = Selector::parse("div.rankings a.ranking")
+        .map_err(|err| anyhow!("Failed to parse selector: {:?}", err))?;
+
+    let mut rankings = Rankings::default();
+
+    for ranking_element in body_document.select(&ranking_selector) {
+        let text = element_text(ranking_element);
+
+        if !text.contains("All Time") {
+            continue;
+        }
+
+        let Some(rank) = parse_amount(text.split_whitespace().next().unwrap_or_default()) else {
+            continue;
+        };
+
+        if text.contains("Highest Rated") {
+            rankings.rated = Some(rank);
+        } else if text.contains("Most Popular") {
+            rankings.popular = Some(rank);
+        }
+    }
+
+    Ok(rankings)
+}
+
+/// Reads the status distribution of the overview, where every list has a name
+/// like "Completed" and an amount like "12,345 Users". `None` when the page
+/// has no chart.
+pub fn parse_body_status_distribution(body_document: &Html) -> Result<Option<StatusDistribution>> {
+    let status_selector = Selector::parse("div.status-distribution div.status")
+        .map_err(|err| anyhow!("Failed to parse selector: {:?}", err))?;
+    let name_selector =
+        Selector::parse(".name").map_err(|err| anyhow!("Failed to parse selector: {:?}", err))?;
+    let amount_selector =
+        Selector::parse(".amount").map_err(|err| anyhow!("Failed to parse selector: {:?}", err))?;
+
+    let mut distribution = StatusDistribution::default();
+    let mut is_found = false;
+
+    for status_element in body_document.select(&status_selector) {
+        let name = status_element
+            .select(&name_selector)
+            .next()
+            .map(element_text);
+        let amount = status_element
+            .select(&amount_selector)
+            .next()
+            .map(element_text)
+            .and_then(|amount| parse_amount(&amount));
+
+        if let (Some(name), Some(amount)) = (name, amount) {
+            distribution.add(&name, amount);
+            is_found = true;
+        }
+    }
+
+    Ok(is_found.then_some(distribution))
+}
+
+/// Parses a number like "#1,234", "12,345 Users" or "76%".
+#[must_use]
+pub fn parse_amount(text: &str) -> Option<i32> {
+    let digits: String = text
+        .trim()
+        .trim_start_matches('#')
+        .chars()
+        .take_while(|letter| letter.is_ascii_digit() || *letter == ',')
+        .filter(char::is_ascii_digit)
+        .collect();
+
+    digits.parse().ok()
+}
+
+fn element_text(element: ElementRef) -> String {
+    element.text().collect::<String>().trim().to_string()
+}
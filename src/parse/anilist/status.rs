@@ -1,22 +1,38 @@
 use std::fmt;
 
-use anyhow::{bail, Result};
-
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Status {
     Releasing,
     Finished,
     NotYetReleased,
     Cancelled,
+    Hiatus,
+    /// A status anilist added since, kept as it was written
+    Unknown(String),
+}
+
+#[must_use]
+pub fn to_status(status: &str) -> Status {
+    match status {
+        "Releasing" => Status::Releasing,
+        "Finished" => Status::Finished,
+        "Not Yet Released" => Status::NotYetReleased,
+        "Cancelled" => Status::Cancelled,
+        "Hiatus" => Status::Hiatus,
+        _ => Status::Unknown(status.to_string()),
+    }
 }
 
-pub fn to_status(status: &str) -> Result<Status> {
+/// Maps the `MediaStatus` value of the GraphQL API.
+#[must_use]
+pub fn api_to_status(status: &str) -> Status {
     match status {
-        "Releasing" => Ok(Status::Releasing),
-        "Finished" => Ok(Status::Finished),
-        "Not Yet Released" => Ok(Status::NotYetReleased),
-        "Cancelled" => Ok(Status::Cancelled),
-        _ => bail!("Unknown status: {}", status),
+        "RELEASING" => Status::Releasing,
+        "FINISHED" => Status::Finished,
+        "NOT_YET_RELEASED" => Status::NotYetReleased,
+        "CANCELLED" => Status::Cancelled,
+        "HIATUS" => Status::Hiatus,
+        _ => Status::Unknown(status.to_string()),
     }
 }
 
@@ -27,6 +43,8 @@ impl fmt::Display for Status {
             Status::Finished => "Finished",
             Status::NotYetReleased => "Not Yet Released",
             Status::Cancelled => "Cancelled",
+            Status::Hiatus => "Hiatus",
+            Status::Unknown(status) => status,
         };
         write!(f, "{}", s)
     }
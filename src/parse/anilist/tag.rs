@@ -0,0 +1,55 @@
+use anyhow::{anyhow, Result};
+use scraper::{Html, Selector};
+
+/// An anilist tag, ranked by how much it applies to the anime.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Tag {
+    pub name: String,
+    /// Like "Setting-Universe", only known from the API
+    pub category: Option<String>,
+    /// Percentage from 0 to 100
+    pub rank: i32,
+}
+
+/// Reads the tags of the sidebar, which lists each one as a name and a rank
+/// like "85%". Spoiler tags are only rendered once revealed, so they are
+/// missing here.
+pub fn parse_body_tags(body_document: &Html) -> Result<Vec<Tag>> {
+    let tag_selector = Selector::parse("div.tags div.tag")
+        .map_err(|err| anyhow!("Failed to parse selector: {:?}", err))?;
+    let name_selector =
+        Selector::parse(".name").map_err(|err| anyhow!("Failed to parse selector: {:?}", err))?;
+    let rank_selector =
+        Selector::parse(".rank").map_err(|err| anyhow!("Failed to parse selector: {:?}", err))?;
+
+    let tags = body_document
+        .select(&tag_selector)
+        .filter_map(|tag_element| {
+            let name = tag_element
+                .select(&name_selector)
+                .next()?
+                .text()
+                .collect::<String>()
+                .trim()
+                .to_string();
+
+            let rank = tag_element
+                .select(&rank_selector)
+                .next()?
+                .text()
+                .collect::<String>()
+                .trim()
+                .trim_end_matches('%')
+                .parse()
+                .ok()?;
+
+            Some(Tag {
+                name,
+                category: None,
+                rank,
+            })
+        })
+        .collect();
+
+    Ok(tags)
+}
@@ -0,0 +1,243 @@
+use std::io::{self, Cursor, Read};
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime};
+use sevenz_rust::{Password, SevenZReader};
+use thiserror::Error;
+use zip::ZipArchive;
+
+/// Largest member read into memory, which subtitles are far below.
+const MAX_CONTENT_SIZE: u64 = 16 * 1024 * 1024;
+
+/// Archive formats that can be opened. Rar files stay opaque.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArchiveFormat {
+    Zip,
+    SevenZ,
+}
+
+impl ArchiveFormat {
+    #[must_use]
+    pub fn from_extension(extension: &str) -> Option<Self> {
+        match extension.to_lowercase().as_str() {
+            "zip" => Some(ArchiveFormat::Zip),
+            "7z" => Some(ArchiveFormat::SevenZ),
+            _ => None,
+        }
+    }
+}
+
+/// A file inside an archive.
+#[derive(Debug, Clone)]
+pub struct ArchiveMember {
+    /// Path inside the archive, with folders separated by "/"
+    pub path: String,
+    /// Unpacked size in bytes
+    pub size: u64,
+    pub last_modified: Option<NaiveDateTime>,
+    /// Unpacked bytes, only read for the members that were asked for
+    pub content: Option<Vec<u8>>,
+}
+
+/// Why an archive could not be opened.
+#[derive(Debug, Error)]
+pub enum ArchiveError {
+    #[error("Failed to read zip archive")]
+    Zip(#[from] zip::result::ZipError),
+    #[error("Failed to read 7z archive")]
+    SevenZ(#[from] sevenz_rust::Error),
+    #[error("Failed to unpack archive member")]
+    Io(#[from] std::io::Error),
+}
+
+/// Lists the files of an archive, skipping folders. The content is read
+/// for the members `read_content` picks by path, unless they are too large.
+pub fn read_archive(
+    bytes: &[u8],
+    format: ArchiveFormat,
+    read_content: impl Fn(&str) -> bool,
+) -> Result<Vec<ArchiveMember>, ArchiveError> {
+    match format {
+        ArchiveFormat::Zip => read_zip(bytes, read_content),
+        ArchiveFormat::SevenZ => read_seven_z(bytes, read_content),
+    }
+}
+
+fn read_zip(
+    bytes: &[u8],
+    read_content: impl Fn(&str) -> bool,
+) -> Result<Vec<ArchiveMember>, ArchiveError> {
+    let mut archive = ZipArchive::new(Cursor::new(bytes))?;
+    let mut members = Vec::new();
+
+    for index in 0..archive.len() {
+        let mut file = archive.by_index(index)?;
+
+        if file.is_dir() {
+            continue;
+        }
+
+        let path = file.name().replace('\\', "/");
+        let size = file.size();
+
+        // zip stores local time without a zone, which is taken as UTC
+        let last_modified = file.last_modified().and_then(|time| {
+            NaiveDate::from_ymd_opt(
+                i32::from(time.year()),
+                u32::from(time.month()),
+                u32::from(time.day()),
+            )?
+            .and_hms_opt(
+                u32::from(time.hour()),
+                u32::from(time.minute()),
+                u32::from(time.second()),
+            )
+        });
+
+        let content = if size <= MAX_CONTENT_SIZE && read_content(&path) {
+            let mut content = Vec::new();
+            file.read_to_end(&mut content)?;
+            Some(content)
+        } else {
+            None
+        };
+
+        members.push(ArchiveMember {
+            path,
+            size,
+            last_modified,
+            content,
+        });
+    }
+
+    Ok(members)
+}
+
+fn read_seven_z(
+    bytes: &[u8],
+    read_content: impl Fn(&str) -> bool,
+) -> Result<Vec<ArchiveMember>, ArchiveError> {
+    let mut archive = SevenZReader::new(Cursor::new(bytes), bytes.len() as u64, Password::empty())?;
+    let mut members = Vec::new();
+
+    // solid archives are unpacked in order, so members that are not wanted
+    // are still read through
+    archive.for_each_entries(|entry, reader| {
+        let path = entry.name().replace('\\', "/");
+        let size = entry.size();
+
+        let content = if !entry.is_directory() && size <= MAX_CONTENT_SIZE && read_content(&path) {
+            let mut content = Vec::new();
+            reader.read_to_end(&mut content)?;
+            Some(content)
+        } else {
+            io::copy(reader, &mut io::sink())?;
+            None
+        };
+
+        if entry.is_directory() {
+            return Ok(true);
+        }
+
+        let last_modified = entry
+            .has_last_modified_date
+            .then(|| DateTime::from_timestamp(entry.last_modified_date().to_unix_time(), 0))
+            .flatten()
+            .map(|time| time.naive_utc());
+
+        members.push(ArchiveMember {
+            path,
+            size,
+            last_modified,
+            content,
+        });
+
+        Ok(true)
+    })?;
+
+    Ok(members)
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use std::io::Write;
+
+    use zip::{write::SimpleFileOptions, ZipWriter};
+
+    use super::*;
+
+    /// A zip of the files with their content, all changed at 2024-03-03
+    /// 11:53:02. Paths ending in "/" are folders.
+    pub(crate) fn zip(files: &[(&str, &[u8])]) -> Vec<u8> {
+        let time = zip::DateTime::from_date_and_time(2024, 3, 3, 11, 53, 2).unwrap();
+        let options = SimpleFileOptions::default().last_modified_time(time);
+        let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+
+        for &(path, content) in files {
+            if path.ends_with('/') {
+                writer.add_directory(path, options).unwrap();
+            } else {
+                writer.start_file(path, options).unwrap();
+                writer.write_all(content).unwrap();
+            }
+        }
+
+        writer.finish().unwrap().into_inner()
+    }
+
+    #[test]
+    fn lists_zip_members_and_reads_the_picked_ones() {
+        let bytes = zip(&[
+            ("Show/", b""),
+            (
+                "Show/Show - 01.srt",
+                b"1\n00:00:01,000 --> 00:00:02,000\nHi\n",
+            ),
+            ("Show/readme.txt", b"thanks"),
+        ]);
+
+        let members =
+            read_archive(&bytes, ArchiveFormat::Zip, |path| path != "Show/readme.txt").unwrap();
+
+        let paths: Vec<_> = members.iter().map(|member| member.path.as_str()).collect();
+        assert_eq!(paths, ["Show/Show - 01.srt", "Show/readme.txt"]);
+
+        assert_eq!(members[0].size, 35);
+        assert_eq!(
+            members[0].last_modified.unwrap().to_string(),
+            "2024-03-03 11:53:02"
+        );
+        assert_eq!(
+            members[0].content.as_deref(),
+            Some(b"1\n00:00:01,000 --> 00:00:02,000\nHi\n".as_slice())
+        );
+        assert_eq!(members[1].content, None);
+    }
+
+    #[test]
+    fn fails_on_corrupt_archives() {
+        let mut bytes = zip(&[("Show - 01.srt", b"subtitles")]);
+        bytes.truncate(bytes.len() / 2);
+
+        assert!(matches!(
+            read_archive(&bytes, ArchiveFormat::Zip, |_| true),
+            Err(ArchiveError::Zip(_))
+        ));
+        assert!(matches!(
+            read_archive(b"not an archive", ArchiveFormat::SevenZ, |_| true),
+            Err(ArchiveError::SevenZ(_))
+        ));
+    }
+
+    #[test]
+    fn knows_the_formats_it_opens() {
+        assert_eq!(
+            ArchiveFormat::from_extension("ZIP"),
+            Some(ArchiveFormat::Zip)
+        );
+        assert_eq!(
+            ArchiveFormat::from_extension("7z"),
+            Some(ArchiveFormat::SevenZ)
+        );
+        assert_eq!(ArchiveFormat::from_extension("rar"), None);
+    }
+}
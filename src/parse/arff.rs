@@ -189,6 +189,13 @@ impl ARFFData {
                         }
                     }
 
+                    // a column without any known value is all zeros
+                    if count == 0 {
+                        means.insert(index, 0.0);
+                        standard_deviations.insert(index, 0.0);
+                        continue;
+                    }
+
                     let mean = sum / count as f64;
                     means.insert(index, mean);
 
@@ -208,13 +215,10 @@ impl ARFFData {
                         }
                     }
 
-                    let mode = frequency
-                        .into_iter()
-                        .max_by_key(|&(_, count)| count)
-                        .unwrap()
-                        .0;
-
-                    modes.insert(index, mode);
+                    // a column without any known value has no mode
+                    if let Some((mode, _)) = frequency.into_iter().max_by_key(|&(_, count)| count) {
+                        modes.insert(index, mode);
+                    }
                 }
                 AttributeType::BooleanNominal => {
                     let mut count = 0;
@@ -265,11 +269,14 @@ impl ARFFData {
                         if attribute.name == "source" {
                             normalized_record.push(value.clone());
                         } else {
-                            let mode = modes.get(&index).unwrap();
-                            let actual_value = if value == "?" { mode } else { value };
+                            let actual_value = if value == "?" {
+                                modes.get(&index)
+                            } else {
+                                Some(value)
+                            };
 
                             for nominal_value in nominal_values {
-                                if nominal_value == actual_value {
+                                if Some(nominal_value) == actual_value {
                                     normalized_record.push("1".to_string());
                                 } else {
                                     normalized_record.push("0".to_string());
@@ -1,2 +1,5 @@
+pub mod api;
 pub mod entry;
+pub mod error;
 pub mod file;
+pub mod file_name;
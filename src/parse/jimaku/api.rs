@@ -0,0 +1,157 @@
+use anyhow::{Context, Result};
+use chrono::DateTime;
+use serde::Deserialize;
+
+use super::{entry::Entry, error::ParseError, file::FileData};
+use crate::fetch::Fetcher;
+
+/// Environment variable the API key is read from.
+pub const API_KEY_VAR: &str = "JIMAKU_API_KEY";
+
+/// An entry as the API returns it, which holds the id the listing page only
+/// has in the link, the flags as booleans and the time as RFC 3339.
+#[derive(Debug, Deserialize)]
+struct ApiEntry {
+    id: i32,
+    name: String,
+    #[serde(default)]
+    flags: ApiFlags,
+    last_modified: String,
+    anilist_id: Option<i32>,
+    tmdb_id: Option<String>,
+    english_name: Option<String>,
+    japanese_name: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+#[allow(clippy::struct_excessive_bools)]
+struct ApiFlags {
+    anime: bool,
+    unverified: bool,
+    external: bool,
+    movie: bool,
+    adult: bool,
+}
+
+impl ApiFlags {
+    /// The flags as the bits the listing page has, in the same order.
+    fn bits(&self) -> u32 {
+        [
+            self.anime,
+            self.unverified,
+            self.external,
+            self.movie,
+            self.adult,
+        ]
+        .into_iter()
+        .enumerate()
+        .filter(|&(_, is_set)| is_set)
+        .map(|(bit, _)| 1 << bit)
+        .sum()
+    }
+}
+
+/// A file as the API returns it, with the download link the page has in
+/// the link.
+#[derive(Debug, Deserialize)]
+struct ApiFile {
+    url: String,
+    name: String,
+    size: i64,
+    last_modified: String,
+}
+
+impl TryFrom<ApiEntry> for Entry {
+    type Error = ParseError;
+
+    fn try_from(api_entry: ApiEntry) -> Result<Self, Self::Error> {
+        let last_modified = DateTime::parse_from_rfc3339(&api_entry.last_modified)
+            .map_err(|_| ParseError::InvalidDate(api_entry.last_modified.clone()))?
+            .timestamp();
+
+        Ok(Entry {
+            id: api_entry.id,
+            name: api_entry.name,
+            flags: api_entry.flags.bits(),
+            last_modified,
+            anilist_id: api_entry.anilist_id,
+            _tmdb_id: api_entry.tmdb_id,
+            english_name: api_entry.english_name,
+            japanese_name: api_entry.japanese_name,
+        })
+    }
+}
+
+impl From<ApiFile> for FileData {
+    fn from(api_file: ApiFile) -> Self {
+        FileData {
+            name: api_file.name,
+            size: api_file.size,
+            last_modified: api_file.last_modified,
+            url: Some(api_file.url),
+            cues: None,
+            members: None,
+        }
+    }
+}
+
+/// Path of the search that lists the anime entries, or the live action ones.
+#[must_use]
+pub fn search_path(anime: bool) -> String {
+    format!("/api/entries/search?anime={anime}")
+}
+
+/// Fetches the entries a search URL of the API lists.
+pub async fn get_entries<F: Fetcher>(
+    fetcher: &F,
+    url: &str,
+    api_key: Option<&str>,
+) -> Result<Vec<Entry>> {
+    let body = fetcher
+        .get_json(url, api_key)
+        .await
+        .context("Failed to get API response")?;
+
+    parse_entries_json(&body)
+}
+
+/// Fetches the files of the entry from the API at `base_url`.
+pub async fn get_files<F: Fetcher>(
+    fetcher: &F,
+    base_url: &str,
+    id: i32,
+    api_key: Option<&str>,
+) -> Result<Vec<FileData>> {
+    let url = format!("{base_url}/api/entries/{id}/files");
+
+    let body = fetcher
+        .get_json(&url, api_key)
+        .await
+        .context("Failed to get API response")?;
+
+    parse_files_json(&body)
+}
+
+pub fn parse_entries_json(body: &str) -> Result<Vec<Entry>> {
+    let entries: Vec<ApiEntry> =
+        serde_json::from_str(body).map_err(|source| ParseError::InvalidJson {
+            what: "API entries",
+            source,
+        })?;
+
+    Ok(entries
+        .into_iter()
+        .map(Entry::try_from)
+        .collect::<Result<_, _>>()?)
+}
+
+pub fn parse_files_json(body: &str) -> Result<Vec<FileData>> {
+    let files: Vec<ApiFile> =
+        serde_json::from_str(body).map_err(|source| ParseError::InvalidJson {
+            what: "API files",
+            source,
+        })?;
+
+    Ok(files.into_iter().map(FileData::from).collect())
+}
@@ -2,6 +2,8 @@ use anyhow::{anyhow, Context, Result};
 use scraper::{Html, Selector};
 use serde::Deserialize;
 
+use super::error::ParseError;
+
 #[derive(Debug, Deserialize)]
 pub struct Entry {
     #[serde(skip_deserializing)]
@@ -10,7 +12,7 @@ pub struct Entry {
     pub flags: u32,
     pub last_modified: i64,
     pub anilist_id: Option<i32>,
-    _tmdb_id: Option<String>,
+    pub(super) _tmdb_id: Option<String>,
     pub english_name: Option<String>,
     pub japanese_name: Option<String>,
 }
@@ -40,8 +42,13 @@ fn parse_data_extra(element: &scraper::ElementRef) -> Result<Entry> {
     let data_extra = element
         .value()
         .attr("data-extra")
-        .context("Failed to get data-extra attribute")?;
-    Ok(serde_json::from_str(data_extra).context("Failed to parse JSON")?)
+        .ok_or(ParseError::MissingElement("data-extra attribute"))?;
+    Ok(
+        serde_json::from_str(data_extra).map_err(|source| ParseError::InvalidJson {
+            what: "entry data-extra",
+            source,
+        })?,
+    )
 }
 
 fn parse_id_from_element(element: &scraper::ElementRef) -> Result<Option<i32>> {
@@ -51,12 +58,12 @@ fn parse_id_from_element(element: &scraper::ElementRef) -> Result<Option<i32>> {
                 .map_err(|e| anyhow!("Failed to parse selector for link: {:?}", e))?,
         )
         .next()
-        .context("Failed to find link element")?;
+        .ok_or(ParseError::MissingElement("link element"))?;
 
     let href = link
         .value()
         .attr("href")
-        .context("Failed to get href attribute")?;
+        .ok_or(ParseError::MissingElement("href attribute"))?;
 
     Ok(href
         .split('/')
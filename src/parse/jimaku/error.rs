@@ -0,0 +1,36 @@
+use thiserror::Error;
+
+/// Why a jimaku listing or entry page could not be parsed, or the entry has
+/// nothing to save.
+#[derive(Debug, Error)]
+pub enum ParseError {
+    #[error("Missing {0}")]
+    MissingElement(&'static str),
+    #[error("Failed to parse {what} JSON")]
+    InvalidJson {
+        what: &'static str,
+        #[source]
+        source: serde_json::Error,
+    },
+    #[error("Invalid date {0}")]
+    InvalidDate(String),
+    #[error("Entry has no anilist id")]
+    NoAnilistId,
+    #[error("Entry has no files")]
+    NoFiles,
+}
+
+impl ParseError {
+    /// Short name of the kind of failure, for the failure summary. Parsing
+    /// the same page again never helps, so there is no transient kind.
+    #[must_use]
+    pub fn category(&self) -> &'static str {
+        match self {
+            ParseError::MissingElement(_) => "missing jimaku element",
+            ParseError::InvalidJson { .. } => "invalid jimaku JSON",
+            ParseError::InvalidDate(_) => "invalid jimaku date",
+            ParseError::NoAnilistId => "no anilist id",
+            ParseError::NoFiles => "no jimaku files",
+        }
+    }
+}
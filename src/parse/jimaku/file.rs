@@ -2,11 +2,23 @@ use anyhow::{anyhow, Context};
 use scraper::{Html, Selector};
 use serde::Deserialize;
 
+use super::error::ParseError;
+use crate::parse::subtitle::cue::Cue;
+
 #[derive(Debug, Deserialize)]
 pub struct FileData {
     pub name: String,
     pub size: i64,
     pub last_modified: String,
+    /// Link to download the file, as written on the page
+    #[serde(skip)]
+    pub url: Option<String>,
+    /// Cues of the file once it was downloaded
+    #[serde(skip)]
+    pub cues: Option<Vec<Cue>>,
+    /// Files inside the archive once it was opened
+    #[serde(skip)]
+    pub members: Option<Vec<FileData>>,
 }
 
 pub fn parse_files_data(body: &str) -> anyhow::Result<Vec<FileData>> {
@@ -29,7 +41,22 @@ fn parse_file_data(element: &scraper::ElementRef) -> anyhow::Result<Option<FileD
     let data_extra = element
         .value()
         .attr("data-extra")
-        .context("Failed to get data-extra attribute")?;
+        .ok_or(ParseError::MissingElement("data-extra attribute"))?;
+
+    let file_data: Option<FileData> =
+        serde_json::from_str(data_extra).map_err(|source| ParseError::InvalidJson {
+            what: "file data-extra",
+            source,
+        })?;
+
+    let link_selector = Selector::parse("a.table-data.file-name")
+        .map_err(|err| anyhow!("Failed to parse selector: {:?}", err))?;
+
+    let url = element
+        .select(&link_selector)
+        .next()
+        .and_then(|link| link.value().attr("href"))
+        .map(str::to_string);
 
-    Ok(serde_json::from_str(data_extra).context("Failed to parse JSON")?)
+    Ok(file_data.map(|file_data| FileData { url, ..file_data }))
 }
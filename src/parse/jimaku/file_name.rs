@@ -0,0 +1,245 @@
+use std::sync::LazyLock;
+
+use regex::Regex;
+
+/// The language a file name marks its subtitles with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Language {
+    Japanese,
+    English,
+}
+
+/// Episodes a file holds, a single one when `first` and `last` are equal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EpisodeRange {
+    pub first: u32,
+    pub last: u32,
+}
+
+/// What a file name like "[Group] Show - 01 (1080p).ja.srt" tells about the
+/// file. Every part is guessed, and is `None` when the name does not have it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileName {
+    /// Lowercase, like "srt" or "zip"
+    pub extension: Option<String>,
+    pub episodes: Option<EpisodeRange>,
+    /// Release group of the video the subtitles were timed to
+    pub group: Option<String>,
+    /// Height of the video, like 1080
+    pub resolution: Option<u32>,
+    pub language: Option<Language>,
+}
+
+static EXTENSION: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\.([A-Za-z0-9]{1,5})$").expect("Valid regex"));
+static GROUP: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^\s*\[([^\]]+)\]").expect("Valid regex"));
+static RESOLUTION: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)\b(?:\d{3,4}x)?(\d{3,4})[pi]?\b").expect("Valid regex"));
+static JAPANESE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)(?:^|[\[(.\s_-])(?:ja|jp|jpn|japanese)(?:[\[\]()\s._-]|$)")
+        .expect("Valid regex")
+});
+static ENGLISH: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)(?:^|[\[(.\s_-])(?:en|eng|english)(?:[\[\]()\s._-]|$)").expect("Valid regex")
+});
+static BRACKETS: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\[[^\]]*\]|\([^)]*\)").expect("Valid regex"));
+static SEASON_EPISODE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)\bS\d{1,2}\s*E(\d{1,4})(?:\s*-\s*E?(\d{1,4}))?").expect("Valid regex")
+});
+static EPISODE_RANGE: LazyLock<Regex> = LazyLock::new(|| {
+    // a hyphen with spaces around it separates the title, like in "S2 - 05"
+    Regex::new(
+        r"(?i)(?:^|[\s_.\-])(?:EP?\s*)?(\d{1,4})(?:-|\s*~\s*|\s+to\s+)(?:EP?\s*)?(\d{1,4})(?:$|[\s_.v])",
+    )
+    .expect("Valid regex")
+});
+static EPISODE_MARKED: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)(?:(?:^|[^a-z])(?:EP?|Episode)|第)\s*\.?\s*(\d{1,4})").expect("Valid regex")
+});
+static EPISODE_NUMBER: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?:^|[\s_.\-])(\d{1,4})(?:v\d)?(?:$|[\s_.])").expect("Valid regex")
+});
+
+/// Guesses the parts of a jimaku file name, or of the path of a file inside
+/// an archive without its folders.
+#[must_use]
+pub fn parse_file_name(name: &str) -> FileName {
+    let name = name.rsplit('/').next().unwrap_or(name).trim();
+
+    let (stem, extension) = match EXTENSION.captures(name) {
+        Some(captures) => (
+            &name[..captures.get(0).map_or(name.len(), |whole| whole.start())],
+            Some(captures[1].to_lowercase()),
+        ),
+        None => (name, None),
+    };
+
+    let group = GROUP
+        .captures(stem)
+        .map(|captures| captures[1].trim().to_string())
+        .filter(|group| !group.is_empty());
+
+    let resolution = RESOLUTION
+        .captures_iter(stem)
+        .filter(|captures| {
+            let whole = &captures[0];
+            whole.ends_with(['p', 'P', 'i', 'I']) || whole.contains(['x', 'X'])
+        })
+        .find_map(|captures| captures[1].parse().ok());
+
+    let language = if JAPANESE.is_match(stem) {
+        Some(Language::Japanese)
+    } else if ENGLISH.is_match(stem) {
+        Some(Language::English)
+    } else {
+        None
+    };
+
+    FileName {
+        extension,
+        episodes: parse_episodes(stem),
+        group,
+        resolution,
+        language,
+    }
+}
+
+/// Looks for the episodes in the name without its bracketed tags, which
+/// hold hashes, resolutions and groups rather than episodes.
+fn parse_episodes(stem: &str) -> Option<EpisodeRange> {
+    let stem = BRACKETS.replace_all(stem, " ");
+
+    let season_episode = SEASON_EPISODE.captures(&stem).and_then(|captures| {
+        let first = captures[1].parse().ok()?;
+        let last = captures
+            .get(2)
+            .and_then(|last| last.as_str().parse().ok())
+            .unwrap_or(first);
+
+        to_range(first, last)
+    });
+
+    let episode_range = || {
+        EPISODE_RANGE
+            .captures(&stem)
+            .and_then(|captures| to_range(captures[1].parse().ok()?, captures[2].parse().ok()?))
+    };
+
+    let episode = || {
+        let captures = EPISODE_MARKED
+            .captures(&stem)
+            .or_else(|| EPISODE_NUMBER.captures_iter(&stem).last())?;
+
+        let episode = captures[1].parse().ok()?;
+        to_range(episode, episode)
+    };
+
+    season_episode.or_else(episode_range).or_else(episode)
+}
+
+/// Skips numbers that are years or ranges that run backwards.
+fn to_range(first: u32, last: u32) -> Option<EpisodeRange> {
+    if first > last || last >= 1900 {
+        return None;
+    }
+
+    Some(EpisodeRange { first, last })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn episodes(name: &str) -> Option<(u32, u32)> {
+        parse_file_name(name)
+            .episodes
+            .map(|range| (range.first, range.last))
+    }
+
+    #[test]
+    fn reads_every_part_of_a_release_name() {
+        assert_eq!(
+            parse_file_name("[SubsPlease] Frieren - 05 (1080p) [A1B2C3D4].ja.SRT"),
+            FileName {
+                extension: Some("srt".to_string()),
+                episodes: Some(EpisodeRange { first: 5, last: 5 }),
+                group: Some("SubsPlease".to_string()),
+                resolution: Some(1080),
+                language: Some(Language::Japanese),
+            }
+        );
+    }
+
+    #[test]
+    fn reads_names_without_any_parts() {
+        assert_eq!(
+            parse_file_name("subtitles"),
+            FileName {
+                extension: None,
+                episodes: None,
+                group: None,
+                resolution: None,
+                language: None,
+            }
+        );
+    }
+
+    #[test]
+    fn reads_extensions_without_folders() {
+        let extension = |name| parse_file_name(name).extension;
+
+        assert_eq!(extension("Show 01.ass"), Some("ass".to_string()));
+        assert_eq!(extension("Show.S01.7z"), Some("7z".to_string()));
+        assert_eq!(extension("Season 1.5/Show 01"), None);
+        assert_eq!(extension("folder.v2/Show 01.vtt"), Some("vtt".to_string()));
+    }
+
+    #[test]
+    fn reads_single_episodes() {
+        assert_eq!(episodes("Show - 01.srt"), Some((1, 1)));
+        assert_eq!(episodes("Show_E12.srt"), Some((12, 12)));
+        assert_eq!(episodes("Show Episode 7.srt"), Some((7, 7)));
+        assert_eq!(episodes("ショー 第3話.srt"), Some((3, 3)));
+        assert_eq!(episodes("Show 2 - 05v2.srt"), Some((5, 5)));
+    }
+
+    #[test]
+    fn reads_episode_ranges() {
+        assert_eq!(episodes("Show 01-12.zip"), Some((1, 12)));
+        assert_eq!(episodes("Show E01 ~ E13.zip"), Some((1, 13)));
+        assert_eq!(episodes("Show 1 to 24.zip"), Some((1, 24)));
+        // a range that runs backwards is not one, the last number is taken
+        assert_eq!(episodes("Show 12-01.zip"), Some((1, 1)));
+    }
+
+    #[test]
+    fn reads_season_episodes() {
+        assert_eq!(episodes("Show.S02E05.1080p.WEB.srt"), Some((5, 5)));
+        assert_eq!(episodes("Show S1E01-E03.srt"), Some((1, 3)));
+        assert_eq!(episodes("Show S1 E10.srt"), Some((10, 10)));
+    }
+
+    #[test]
+    fn skips_years_and_bracketed_numbers() {
+        assert_eq!(episodes("Show (2019) - 04.srt"), Some((4, 4)));
+        assert_eq!(episodes("Show 2019.srt"), None);
+        assert_eq!(episodes("[Group] Show [01].srt"), None);
+    }
+
+    #[test]
+    fn reads_resolutions_and_languages() {
+        let name = parse_file_name("Show 01 1920x1080.en.srt");
+        assert_eq!(name.resolution, Some(1080));
+        assert_eq!(name.language, Some(Language::English));
+
+        let name = parse_file_name("Show 01 [720p][JPN].ass");
+        assert_eq!(name.resolution, Some(720));
+        assert_eq!(name.language, Some(Language::Japanese));
+
+        // a bare number is an episode, not a resolution
+        assert_eq!(parse_file_name("Show 1080.srt").resolution, None);
+        assert_eq!(parse_file_name("Jason 01.srt").language, None);
+    }
+}
@@ -0,0 +1,8 @@
+pub mod ass;
+pub mod cue;
+pub mod encoding;
+pub mod format;
+pub mod srt;
+pub mod text;
+pub mod timestamp;
+pub mod vtt;
@@ -0,0 +1,115 @@
+use super::{cue::Cue, text::strip_ass_tags, timestamp::parse_timestamp};
+
+/// Columns of the events when the file has no "Format:" line.
+const DEFAULT_FORMAT: [&str; 10] = [
+    "Layer", "Start", "End", "Style", "Name", "MarginL", "MarginR", "MarginV", "Effect", "Text",
+];
+
+/// Reads the "Dialogue:" lines of the `[Events]` section of an ASS or SSA
+/// file, with the columns its "Format:" line lists. Comments and lines of
+/// other sections are skipped.
+#[must_use]
+pub fn parse_ass(content: &str) -> Vec<Cue> {
+    let mut cues = Vec::new();
+    let mut in_events = false;
+    let mut format: Vec<String> = DEFAULT_FORMAT.iter().map(ToString::to_string).collect();
+
+    for line in content.lines() {
+        let line = line.trim();
+
+        if line.starts_with('[') {
+            in_events = line.eq_ignore_ascii_case("[Events]");
+            continue;
+        }
+
+        if !in_events {
+            continue;
+        }
+
+        if let Some(columns) = line.strip_prefix("Format:") {
+            format = columns
+                .split(',')
+                .map(|column| column.trim().to_string())
+                .collect();
+        } else if let Some(fields) = line.strip_prefix("Dialogue:") {
+            cues.extend(parse_dialogue(&format, fields));
+        }
+    }
+
+    cues
+}
+
+/// The text is the last column and may hold commas itself.
+fn parse_dialogue(format: &[String], fields: &str) -> Option<Cue> {
+    let fields: Vec<_> = fields.splitn(format.len(), ',').collect();
+
+    let field = |name: &str| {
+        format
+            .iter()
+            .position(|column| column.eq_ignore_ascii_case(name))
+            .and_then(|index| fields.get(index))
+            .map(|field| field.trim())
+    };
+
+    let start = parse_timestamp(field("Start")?)?;
+    let end = parse_timestamp(field("End")?)?;
+
+    Some(Cue {
+        start: start.min(end),
+        end: start.max(end),
+        text: strip_ass_tags(field("Text")?),
+        style: field("Style")
+            .map(|style| style.trim_start_matches('*').to_string())
+            .filter(|style| !style.is_empty()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_dialogue_of_the_events_section() {
+        let content = "[Script Info]\nTitle: Test\nDialogue: not, an, event\n\n\
+            [Events]\n\
+            Format: Layer, Start, End, Style, Name, MarginL, MarginR, MarginV, Effect, Text\n\
+            Comment: 0,0:00:00.00,0:00:01.00,Default,,0,0,0,,Skipped\n\
+            Dialogue: 0,0:00:01.50,0:00:03.00,*Main,,0,0,0,,{\\i1}Hello{\\i0}, world\n\
+            Dialogue: 0,0:00:05.00,0:00:04.00,,,0,0,0,,Swapped\n";
+
+        assert_eq!(
+            parse_ass(content),
+            [
+                Cue {
+                    start: 1500,
+                    end: 3000,
+                    text: "Hello, world".to_string(),
+                    style: Some("Main".to_string()),
+                },
+                Cue {
+                    start: 4000,
+                    end: 5000,
+                    text: "Swapped".to_string(),
+                    style: None,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn follows_the_format_line() {
+        let content = "[Events]\nFormat: Start, End, Text\nDialogue: 0:00:01.00,0:00:02.00,Hi";
+
+        let cues = parse_ass(content);
+        assert_eq!(cues.len(), 1);
+        assert_eq!((cues[0].start, cues[0].end), (1000, 2000));
+        assert_eq!(cues[0].text, "Hi");
+    }
+
+    #[test]
+    fn skips_dialogue_with_broken_times() {
+        let content = "[Events]\nDialogue: 0,soon,0:00:02.00,Default,,0,0,0,,Hi";
+
+        assert_eq!(parse_ass(content), []);
+    }
+}
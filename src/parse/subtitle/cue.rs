@@ -0,0 +1,18 @@
+/// A line of a subtitle file shown between two times.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cue {
+    /// Milliseconds from the start of the video
+    pub start: i64,
+    pub end: i64,
+    /// Text without formatting tags, lines joined by "\n"
+    pub text: String,
+    /// ASS style or `WebVTT` voice of the cue, SRT has neither
+    pub style: Option<String>,
+}
+
+impl Cue {
+    #[must_use]
+    pub fn duration(&self) -> i64 {
+        (self.end - self.start).max(0)
+    }
+}
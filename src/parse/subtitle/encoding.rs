@@ -0,0 +1,103 @@
+use std::borrow::Cow;
+
+use encoding_rs::{Encoding, SHIFT_JIS, UTF_16BE, UTF_16LE, UTF_8};
+
+/// Decodes a subtitle file into text. A byte order mark decides the encoding
+/// when there is one. Otherwise the file is read as UTF-16 when every other
+/// byte is zero, as UTF-8 when it is valid, and as Shift-JIS, which older
+/// Japanese subtitles use, when it is neither. UTF-16 goes first, as the
+/// zeros of mostly ASCII text are valid UTF-8 too.
+#[must_use]
+pub fn decode(bytes: &[u8]) -> String {
+    let encoding = match Encoding::for_bom(bytes) {
+        Some((encoding, _)) => encoding,
+        None => guess_utf_16(bytes).unwrap_or_else(|| {
+            if std::str::from_utf8(bytes).is_ok() {
+                UTF_8
+            } else {
+                SHIFT_JIS
+            }
+        }),
+    };
+
+    // strips the byte order mark of the encoding
+    let (content, _, _) = encoding.decode(bytes);
+
+    match content {
+        Cow::Borrowed(content) => content.to_string(),
+        Cow::Owned(content) => content,
+    }
+}
+
+/// Text of mostly ASCII characters, as timestamps are, has a zero as the
+/// high byte of most UTF-16 code units.
+fn guess_utf_16(bytes: &[u8]) -> Option<&'static Encoding> {
+    let units = bytes.len() / 2;
+    if units == 0 {
+        return None;
+    }
+
+    let zeros_at = |offset: usize| {
+        bytes
+            .iter()
+            .skip(offset)
+            .step_by(2)
+            .filter(|&&byte| byte == 0)
+            .count()
+    };
+
+    if zeros_at(1) * 2 > units {
+        Some(UTF_16LE)
+    } else if zeros_at(0) * 2 > units {
+        Some(UTF_16BE)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEXT: &str = "00:00:01,000 --> 00:00:02,500\nこんにちは";
+
+    fn utf_16(text: &str, to_bytes: fn(u16) -> [u8; 2]) -> Vec<u8> {
+        text.encode_utf16().flat_map(to_bytes).collect()
+    }
+
+    #[test]
+    fn reads_utf_8() {
+        assert_eq!(decode(TEXT.as_bytes()), TEXT);
+    }
+
+    #[test]
+    fn strips_the_byte_order_mark() {
+        let bytes = [b"\xEF\xBB\xBF".as_slice(), TEXT.as_bytes()].concat();
+        assert_eq!(decode(&bytes), TEXT);
+
+        let bytes = [[0xFF, 0xFE].as_slice(), &utf_16(TEXT, u16::to_le_bytes)].concat();
+        assert_eq!(decode(&bytes), TEXT);
+    }
+
+    #[test]
+    fn guesses_utf_16_without_a_byte_order_mark() {
+        assert_eq!(decode(&utf_16(TEXT, u16::to_le_bytes)), TEXT);
+        assert_eq!(decode(&utf_16(TEXT, u16::to_be_bytes)), TEXT);
+
+        // ASCII in UTF-16 is valid UTF-8 as well
+        let ascii = "00:00:01,000 --> 00:00:02,500\nHello";
+        assert_eq!(decode(&utf_16(ascii, u16::to_le_bytes)), ascii);
+        assert_eq!(decode(&utf_16(ascii, u16::to_be_bytes)), ascii);
+    }
+
+    #[test]
+    fn falls_back_to_shift_jis() {
+        let (bytes, _, _) = SHIFT_JIS.encode(TEXT);
+        assert_eq!(decode(&bytes), TEXT);
+    }
+
+    #[test]
+    fn reads_empty_files() {
+        assert_eq!(decode(b""), "");
+    }
+}
@@ -0,0 +1,56 @@
+use super::{ass::parse_ass, cue::Cue, encoding::decode, srt::parse_srt, vtt::parse_vtt};
+
+/// The subtitle formats jimaku hosts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SubtitleFormat {
+    Srt,
+    Ass,
+    Vtt,
+}
+
+impl SubtitleFormat {
+    #[must_use]
+    pub fn from_extension(extension: &str) -> Option<Self> {
+        match extension.to_lowercase().as_str() {
+            "srt" => Some(SubtitleFormat::Srt),
+            "ass" | "ssa" => Some(SubtitleFormat::Ass),
+            "vtt" => Some(SubtitleFormat::Vtt),
+            _ => None,
+        }
+    }
+
+    /// Guesses the format from the start of the content, for files named
+    /// with the wrong extension.
+    #[must_use]
+    pub fn detect(content: &str) -> Option<Self> {
+        let start = content.trim_start();
+
+        if start.starts_with("WEBVTT") {
+            Some(SubtitleFormat::Vtt)
+        } else if start.starts_with("[Script Info]") || content.contains("\nDialogue:") {
+            Some(SubtitleFormat::Ass)
+        } else if content.contains("-->") {
+            Some(SubtitleFormat::Srt)
+        } else {
+            None
+        }
+    }
+}
+
+/// Reads the cues of a subtitle file in any encoding. The content decides
+/// the format when it clearly differs from the extension. Lines that cannot
+/// be read are skipped, so a broken file only has fewer cues, and files of
+/// other formats have none.
+#[must_use]
+pub fn parse_subtitle(bytes: &[u8], extension: &str) -> Vec<Cue> {
+    let content = decode(bytes);
+
+    let format = SubtitleFormat::detect(&content).or(SubtitleFormat::from_extension(extension));
+
+    match format {
+        Some(SubtitleFormat::Srt) => parse_srt(&content),
+        Some(SubtitleFormat::Ass) => parse_ass(&content),
+        Some(SubtitleFormat::Vtt) => parse_vtt(&content),
+        None => Vec::new(),
+    }
+}
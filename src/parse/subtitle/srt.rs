@@ -0,0 +1,102 @@
+use super::{
+    cue::Cue,
+    text::{strip_ass_tags, strip_html_tags},
+    timestamp::parse_timing,
+};
+
+/// Reads SRT blocks of an optional counter, a timing line like
+/// "00:00:01,000 --> 00:00:02,500" and the text up to an empty line.
+/// Counters are not checked, as they are often missing or wrong.
+#[must_use]
+pub fn parse_srt(content: &str) -> Vec<Cue> {
+    let mut cues = Vec::new();
+    let mut lines = content.lines().peekable();
+
+    while let Some(line) = lines.next() {
+        let Some((start, end)) = parse_timing(line) else {
+            continue;
+        };
+
+        let mut text = Vec::new();
+
+        // a missing empty line still ends the text at the next timing line
+        while let Some(line) = lines.next_if(|line| parse_timing(line).is_none()) {
+            if line.trim().is_empty() {
+                break;
+            }
+
+            text.push(strip_ass_tags(&strip_html_tags(line)));
+        }
+
+        // drops the counter of the next block, read before its timing line
+        if lines
+            .peek()
+            .is_some_and(|line| parse_timing(line).is_some())
+            && text.last().is_some_and(|line| is_counter(line))
+        {
+            text.pop();
+        }
+
+        cues.push(Cue {
+            start,
+            end,
+            text: text.join("\n"),
+            style: None,
+        });
+    }
+
+    cues
+}
+
+fn is_counter(line: &str) -> bool {
+    !line.is_empty() && line.bytes().all(|byte| byte.is_ascii_digit())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cue(start: i64, end: i64, text: &str) -> Cue {
+        Cue {
+            start,
+            end,
+            text: text.to_string(),
+            style: None,
+        }
+    }
+
+    #[test]
+    fn reads_blocks() {
+        let content = "1\n00:00:01,000 --> 00:00:02,500\n<i>Hello</i>\n{\\an8}world\n\n\
+            2\n00:00:03,000 --> 00:00:04,000\nBye\n";
+
+        assert_eq!(
+            parse_srt(content),
+            [cue(1000, 2500, "Hello\nworld"), cue(3000, 4000, "Bye")]
+        );
+    }
+
+    #[test]
+    fn reads_blocks_without_counters_or_empty_lines() {
+        let content = "00:00:01,000 --> 00:00:02,000\r\nOne\r\n\
+            2\r\n00:00:03,000 --> 00:00:04,000\r\nTwo\r\n\
+            00:00:05,000 --> 00:00:06,000\r\nThree";
+
+        assert_eq!(
+            parse_srt(content),
+            [
+                cue(1000, 2000, "One"),
+                cue(3000, 4000, "Two"),
+                cue(5000, 6000, "Three"),
+            ]
+        );
+    }
+
+    #[test]
+    fn skips_lines_outside_blocks() {
+        assert_eq!(
+            parse_srt("not a subtitle\n\n1\nbroken --> timing\nText"),
+            []
+        );
+    }
+}
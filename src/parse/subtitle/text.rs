@@ -0,0 +1,52 @@
+/// Drops tags like "<i>" or "<font color=...>" and decodes the entities
+/// SRT and `WebVTT` files escape text with.
+#[must_use]
+pub fn strip_html_tags(line: &str) -> String {
+    let mut text = String::new();
+    let mut in_tag = false;
+
+    for letter in line.chars() {
+        match letter {
+            '<' => in_tag = true,
+            '>' if in_tag => in_tag = false,
+            _ if !in_tag => text.push(letter),
+            _ => {}
+        }
+    }
+
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&nbsp;", " ")
+        .replace("&lrm;", "")
+        .replace("&rlm;", "")
+        .replace("&amp;", "&")
+        .trim()
+        .to_string()
+}
+
+/// Drops override blocks like "{\an8}" and turns "\N" into a line break.
+/// SRT files converted from ASS often keep the blocks too.
+#[must_use]
+pub fn strip_ass_tags(text: &str) -> String {
+    let mut stripped = String::new();
+    let mut in_block = false;
+
+    for letter in text.chars() {
+        match letter {
+            '{' => in_block = true,
+            '}' if in_block => in_block = false,
+            _ if !in_block => stripped.push(letter),
+            _ => {}
+        }
+    }
+
+    stripped
+        .replace("\\N", "\n")
+        .replace("\\n", "\n")
+        .replace("\\h", " ")
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
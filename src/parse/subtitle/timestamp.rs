@@ -0,0 +1,112 @@
+/// Parses a timestamp into milliseconds, tolerating what subtitle editors
+/// get wrong. "01:02:03,456", "01:02:03.45", "02:03.456", "1:2:3,4",
+/// "01:02:03:456" and spaces around the parts are all read, while a time too
+/// large to represent is `None`.
+#[must_use]
+pub fn parse_timestamp(timestamp: &str) -> Option<i64> {
+    let timestamp: String = timestamp
+        .chars()
+        .filter(|letter| !letter.is_whitespace())
+        .collect();
+
+    let (clock, fraction) = match timestamp.split_once([',', '.']) {
+        Some((clock, fraction)) => (clock, Some(fraction)),
+        None => (timestamp.as_str(), None),
+    };
+
+    let mut parts: Vec<_> = clock.split(':').collect();
+
+    // a colon before the milliseconds, like "01:02:03:456"
+    let fraction = match fraction {
+        Some(fraction) => fraction,
+        None if parts.len() == 4 => parts.pop()?,
+        None => "0",
+    };
+
+    if parts.is_empty() || parts.len() > 3 {
+        return None;
+    }
+
+    let mut seconds: i64 = 0;
+    for part in parts {
+        if part.is_empty() || !part.bytes().all(|byte| byte.is_ascii_digit()) {
+            return None;
+        }
+        seconds = seconds
+            .checked_mul(60)?
+            .checked_add(part.parse::<i64>().ok()?)?;
+    }
+
+    if !fraction.bytes().all(|byte| byte.is_ascii_digit()) {
+        return None;
+    }
+
+    // "45" is 450 ms in ASS, "456" is 456 ms in SRT, digits past the
+    // milliseconds are dropped
+    let milliseconds: String = fraction.chars().chain("000".chars()).take(3).collect();
+
+    seconds
+        .checked_mul(1000)?
+        .checked_add(milliseconds.parse::<i64>().ok()?)
+}
+
+/// Reads a timing line like "00:00:01,000 --> 00:00:02,500", skipping
+/// `WebVTT` settings after the end time. A start after the end is swapped,
+/// as it is most likely a typo rather than a cue that is never shown.
+#[must_use]
+pub fn parse_timing(line: &str) -> Option<(i64, i64)> {
+    let (start, end) = line.split_once("-->")?;
+    let end = end.split_whitespace().next()?;
+
+    let start = parse_timestamp(start)?;
+    let end = parse_timestamp(end)?;
+
+    Some((start.min(end), start.max(end)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_timestamps_of_every_format() {
+        assert_eq!(parse_timestamp("01:02:03,456"), Some(3_723_456));
+        assert_eq!(parse_timestamp("01:02:03.456"), Some(3_723_456));
+        assert_eq!(parse_timestamp("1:02:03.45"), Some(3_723_450));
+        assert_eq!(parse_timestamp("02:03.456"), Some(123_456));
+        assert_eq!(parse_timestamp("1:2:3,4"), Some(3_723_400));
+        assert_eq!(parse_timestamp("01:02:03:456"), Some(3_723_456));
+        assert_eq!(parse_timestamp(" 01 : 02 : 03 , 456 "), Some(3_723_456));
+        assert_eq!(parse_timestamp("01:02:03"), Some(3_723_000));
+        assert_eq!(parse_timestamp("00:00:01,2345"), Some(1_234));
+    }
+
+    #[test]
+    fn rejects_broken_timestamps() {
+        assert_eq!(parse_timestamp(""), None);
+        assert_eq!(parse_timestamp("01:02:03,4x"), None);
+        assert_eq!(parse_timestamp("01:-2:03,456"), None);
+        assert_eq!(parse_timestamp("1:2:3:4:5"), None);
+        assert_eq!(parse_timestamp("99999999999999999999:00:00"), None);
+        assert_eq!(parse_timestamp("9999999999999999:00:00"), None);
+    }
+
+    #[test]
+    fn reads_timing_lines() {
+        assert_eq!(
+            parse_timing("00:00:01,000 --> 00:00:02,500"),
+            Some((1000, 2500))
+        );
+        assert_eq!(
+            parse_timing("00:01.000 --> 00:02.500 align:start position:10%"),
+            Some((1000, 2500))
+        );
+        // a start after the end is swapped
+        assert_eq!(
+            parse_timing("00:00:02,500 --> 00:00:01,000"),
+            Some((1000, 2500))
+        );
+        assert_eq!(parse_timing("00:00:01,000 -> 00:00:02,500"), None);
+        assert_eq!(parse_timing("Hello --> world"), None);
+    }
+}
@@ -0,0 +1,116 @@
+use super::{cue::Cue, text::strip_html_tags, timestamp::parse_timing};
+
+/// Reads `WebVTT` cues, skipping the header and the NOTE, STYLE and REGION
+/// blocks. The voice of a "<v Name>" tag becomes the style of the cue.
+#[must_use]
+pub fn parse_vtt(content: &str) -> Vec<Cue> {
+    let mut cues = Vec::new();
+
+    for block in blocks(content) {
+        if block[0].starts_with("NOTE")
+            || block[0].starts_with("STYLE")
+            || block[0].starts_with("REGION")
+        {
+            continue;
+        }
+
+        let Some(timing_index) = block.iter().position(|line| line.contains("-->")) else {
+            // the header
+            continue;
+        };
+
+        let Some((start, end)) = parse_timing(block[timing_index]) else {
+            continue;
+        };
+
+        let lines = &block[timing_index + 1..];
+
+        let style = lines.iter().find_map(|line| voice(line));
+        let text: Vec<_> = lines
+            .iter()
+            .map(|line| strip_html_tags(line))
+            .filter(|line| !line.is_empty())
+            .collect();
+
+        cues.push(Cue {
+            start,
+            end,
+            text: text.join("\n"),
+            style,
+        });
+    }
+
+    cues
+}
+
+/// Splits the content at empty lines.
+fn blocks(content: &str) -> Vec<Vec<&str>> {
+    let mut blocks = Vec::new();
+    let mut block = Vec::new();
+
+    for line in content.lines() {
+        if line.trim().is_empty() {
+            if !block.is_empty() {
+                blocks.push(std::mem::take(&mut block));
+            }
+        } else {
+            block.push(line);
+        }
+    }
+
+    if !block.is_empty() {
+        blocks.push(block);
+    }
+
+    blocks
+}
+
+/// The name in a voice tag like "<v Roger>" or "<v.loud Roger>".
+fn voice(line: &str) -> Option<String> {
+    let start = line.find("<v")?;
+    let tag = &line[start + 2..];
+    let tag = &tag[..tag.find('>')?];
+
+    // skips classes like ".loud", which come before the name
+    let (_, name) = tag.split_once(char::is_whitespace)?;
+    let name = name.trim();
+
+    (!name.is_empty()).then(|| name.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_cues_and_skips_other_blocks() {
+        let content = "WEBVTT - Test\n\n\
+            NOTE a comment --> with an arrow\n\n\
+            STYLE\n::cue { color: red }\n\n\
+            intro\n00:01.000 --> 00:02.500 line:0\n<v.loud Roger>Hello</v>\n<b>world</b>\n\n\
+            00:00:03.000 --> 00:00:04.000\nBye\n";
+
+        assert_eq!(
+            parse_vtt(content),
+            [
+                Cue {
+                    start: 1000,
+                    end: 2500,
+                    text: "Hello\nworld".to_string(),
+                    style: Some("Roger".to_string()),
+                },
+                Cue {
+                    start: 3000,
+                    end: 4000,
+                    text: "Bye".to_string(),
+                    style: None,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn skips_cues_with_broken_timings() {
+        assert_eq!(parse_vtt("WEBVTT\n\n00:01 --> later\nText"), []);
+    }
+}
@@ -1,42 +1,222 @@
-use std::{sync::Arc, thread::sleep, time::Duration};
+use std::time::Duration;
 
 use anyhow::{bail, Context, Result};
-use headless_chrome::{Browser, Tab};
-use reqwest;
-use tokio::time::timeout;
+use chrono::{DateTime, Utc};
+use headless_chrome::Tab;
+use reqwest::{
+    header::{ACCEPT, AUTHORIZATION, CONTENT_TYPE, RETRY_AFTER},
+    RequestBuilder, Response, StatusCode,
+};
+use tokio::time::sleep;
+
+use self::{error::FetchError, rate_limit::RateLimiter};
+
+pub mod error;
+pub mod rate_limit;
+
+/// Builds the HTTP client, which gives up on a request after `timeout` and
+/// on connecting after `connect_timeout`, so a stalled server fails the
+/// request like a hung tab instead of blocking the crawl.
+pub fn build_client(timeout: Duration, connect_timeout: Duration) -> Result<reqwest::Client> {
+    reqwest::Client::builder()
+        .timeout(timeout)
+        .connect_timeout(connect_timeout)
+        .build()
+        .context("Failed to build HTTP client")
+}
+
+/// How often and how long to wait before retrying a failed request.
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    pub retries: u64,
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+}
+
+impl RetryPolicy {
+    /// Exponential backoff with full jitter.
+    fn delay(&self, attempt: u64) -> Duration {
+        let exponent = u32::try_from(attempt).unwrap_or(u32::MAX).min(16);
+        let ceiling = self
+            .base_delay
+            .saturating_mul(2_u32.pow(exponent))
+            .min(self.max_delay);
+
+        ceiling.mul_f64(rand::random::<f64>())
+    }
+}
+
+pub async fn get_body(
+    client: &reqwest::Client,
+    url: &str,
+    policy: &RetryPolicy,
+    limiter: &RateLimiter,
+) -> Result<String> {
+    let response = get_response(client.get(url), url, policy, limiter).await?;
+
+    let body = response
+        .text()
+        .await
+        .map_err(|err| network_error(url, err))?;
 
-pub async fn get_body(url: &str) -> Result<String> {
-    let response = get_response(url).await?;
+    Ok(body)
+}
+
+/// Downloads the body as raw bytes, for files that may not be UTF-8.
+pub async fn get_bytes(
+    client: &reqwest::Client,
+    url: &str,
+    policy: &RetryPolicy,
+    limiter: &RateLimiter,
+) -> Result<Vec<u8>> {
+    let response = get_response(client.get(url), url, policy, limiter).await?;
+
+    let bytes = response
+        .bytes()
+        .await
+        .map_err(|err| network_error(url, err))?;
+
+    Ok(bytes.to_vec())
+}
+
+/// Gets a JSON API response, sending the API key as the `Authorization`
+/// header when there is one.
+pub async fn get_json(
+    client: &reqwest::Client,
+    url: &str,
+    api_key: Option<&str>,
+    policy: &RetryPolicy,
+    limiter: &RateLimiter,
+) -> Result<String> {
+    let mut request = client.get(url).header(ACCEPT, "application/json");
+
+    if let Some(api_key) = api_key {
+        request = request.header(AUTHORIZATION, api_key);
+    }
+
+    let response = get_response(request, url, policy, limiter).await?;
 
     let body = response
         .text()
         .await
-        .context("Failed to get response text")?;
+        .map_err(|err| network_error(url, err))?;
 
     Ok(body)
 }
 
-async fn get_response(url: &str) -> Result<reqwest::Response> {
-    for attempt in 0..10 {
-        let response = reqwest::get(url).await;
-        match response {
+pub async fn post_json(
+    client: &reqwest::Client,
+    url: &str,
+    payload: &str,
+    policy: &RetryPolicy,
+    limiter: &RateLimiter,
+) -> Result<String> {
+    let request = client
+        .post(url)
+        .header(CONTENT_TYPE, "application/json")
+        .header(ACCEPT, "application/json")
+        .body(payload.to_string());
+
+    let response = get_response(request, url, policy, limiter).await?;
+
+    let body = response
+        .text()
+        .await
+        .map_err(|err| network_error(url, err))?;
+
+    Ok(body)
+}
+
+async fn get_response(
+    request: RequestBuilder,
+    url: &str,
+    policy: &RetryPolicy,
+    limiter: &RateLimiter,
+) -> Result<Response> {
+    let mut attempt = 0;
+
+    loop {
+        limiter.acquire(url).await;
+
+        let request = request.try_clone().context("Failed to clone request")?;
+
+        let retry_after = match request.send().await {
+            Ok(response) if is_retryable(response.status()) => {
+                if attempt >= policy.retries {
+                    bail!(FetchError::Status {
+                        url: url.to_string(),
+                        status: response.status(),
+                    });
+                }
+                get_retry_after(&response)
+            }
+            Ok(response) if !response.status().is_success() => {
+                bail!(FetchError::Status {
+                    url: url.to_string(),
+                    status: response.status(),
+                });
+            }
             Ok(response) => return Ok(response),
-            Err(_) => sleep(Duration::from_secs(1 * attempt)),
+            Err(err) => {
+                if attempt >= policy.retries {
+                    bail!(network_error(url, err));
+                }
+                None
+            }
+        };
+
+        let delay = match retry_after {
+            Some(retry_after) => {
+                limiter.block(url, retry_after).await;
+                retry_after
+            }
+            None => policy.delay(attempt),
         };
+
+        sleep(delay).await;
+        attempt += 1;
     }
+}
 
-    reqwest::get(url).await.context("Failed to get URL")
+fn network_error(url: &str, err: reqwest::Error) -> FetchError {
+    if err.is_timeout() {
+        FetchError::Timeout {
+            url: url.to_string(),
+        }
+    } else {
+        FetchError::Network {
+            url: url.to_string(),
+            source: err,
+        }
+    }
 }
 
-pub async fn get_page_data_chrome(browser: &Browser, url: &str) -> Result<(String, String)> {
-    let tab = browser.new_tab().context("Failed to create new tab")?;
+fn is_retryable(status: StatusCode) -> bool {
+    status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+}
+
+/// Reads `Retry-After`, given either in seconds or as an HTTP date.
+fn get_retry_after(response: &Response) -> Option<Duration> {
+    let value = response.headers().get(RETRY_AFTER)?.to_str().ok()?.trim();
+
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+
+    let date = DateTime::parse_from_rfc2822(value).ok()?;
 
+    (date.with_timezone(&Utc) - Utc::now()).to_std().ok()
+}
+
+/// Loads the URL in the tab and returns its head and full document. Blocks
+/// until the page is rendered, so call it off the async runtime.
+pub fn get_page_data_chrome(tab: &Tab, url: &str) -> Result<(String, String)> {
     tab.navigate_to(url).context("Failed to navigate to URL")?;
     tab.wait_until_navigated()
         .context("Failed to wait until navigated")?;
 
-    wait_for_element(&tab, "head", Duration::from_secs(10)).await?;
-    wait_for_element(&tab, "body", Duration::from_secs(10)).await?;
+    wait_for_element(tab, "head", Duration::from_secs(10))?;
+    wait_for_element(tab, "body", Duration::from_secs(10))?;
 
     let head_content = tab
         .evaluate("document.head.outerHTML", true)
@@ -49,29 +229,24 @@ pub async fn get_page_data_chrome(browser: &Browser, url: &str) -> Result<(Strin
 
     let body_content = tab.get_content().context("Failed to get body content")?;
 
-    close_tab_with_retry(tab)?;
-
     Ok((head_content, body_content))
 }
 
-async fn wait_for_element(tab: &Tab, element: &str, duration: Duration) -> Result<()> {
-    let wait_for_element = async {
-        tab.wait_for_element(element)
-            .context(format!("Failed to wait for {} element", element))?;
-        Ok(())
-    };
+fn wait_for_element(tab: &Tab, element: &str, duration: Duration) -> Result<()> {
+    tab.wait_for_element_with_custom_timeout(element, duration)
+        .context(format!("Timed out waiting for {element} element"))?;
 
-    match timeout(duration, wait_for_element).await {
-        Ok(result) => result,
-        Err(_) => bail!("Timed out waiting for {} element", element),
-    }
+    Ok(())
 }
 
-fn close_tab_with_retry(tab: Arc<Tab>) -> Result<()> {
+/// Closes the tab, giving Chrome a moment between attempts.
+pub fn close_tab_with_retry(tab: &Tab) -> Result<()> {
     for _ in 0..10 {
-        if let Ok(_) = tab.close(false) {
+        if tab.close(false).is_ok() {
             return Ok(());
         }
+
+        std::thread::sleep(Duration::from_millis(100));
     }
 
     tab.close(false).context("Failed to close tab")?;
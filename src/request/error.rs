@@ -0,0 +1,60 @@
+use reqwest::StatusCode;
+use thiserror::Error;
+
+/// Why a page or response could not be fetched.
+#[derive(Debug, Error)]
+pub enum FetchError {
+    #[error("Request to {url} failed with status {status}")]
+    Status { url: String, status: StatusCode },
+    #[error("Request to {url} timed out")]
+    Timeout { url: String },
+    #[error("Request to {url} failed")]
+    Network {
+        url: String,
+        #[source]
+        source: reqwest::Error,
+    },
+    #[error("Browser failed to load {url}: {message}")]
+    Browser { url: String, message: String },
+    #[error("{url} is not cached")]
+    NotCached { url: String },
+}
+
+impl FetchError {
+    /// Whether fetching again cannot help, like for a missing page.
+    #[must_use]
+    pub fn is_permanent(&self) -> bool {
+        match self {
+            FetchError::Status { status, .. } => {
+                status.is_client_error()
+                    && *status != StatusCode::REQUEST_TIMEOUT
+                    && *status != StatusCode::TOO_MANY_REQUESTS
+            }
+            FetchError::NotCached { .. } => true,
+            FetchError::Timeout { .. }
+            | FetchError::Network { .. }
+            | FetchError::Browser { .. } => false,
+        }
+    }
+
+    /// Short name of the kind of failure, for the failure summary.
+    #[must_use]
+    pub fn category(&self) -> &'static str {
+        match self {
+            FetchError::Status { status, .. }
+                if *status == StatusCode::NOT_FOUND || *status == StatusCode::GONE =>
+            {
+                "page not found"
+            }
+            FetchError::Status { status, .. } if *status == StatusCode::TOO_MANY_REQUESTS => {
+                "rate limited"
+            }
+            FetchError::Status { status, .. } if status.is_server_error() => "server error",
+            FetchError::Status { .. } => "request rejected",
+            FetchError::Timeout { .. } => "timeout",
+            FetchError::Network { .. } => "network error",
+            FetchError::Browser { .. } => "browser error",
+            FetchError::NotCached { .. } => "not cached",
+        }
+    }
+}
@@ -0,0 +1,139 @@
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+use tokio::{sync::Mutex, time::sleep};
+
+/// Lowest rate a host can be limited to, one request every 1000 seconds.
+pub const MIN_RATE: f64 = 0.001;
+
+/// Longest a request waits for a token before checking the bucket again.
+const MAX_WAIT: Duration = Duration::from_secs(1000);
+
+/// Per-host token buckets shared by every fetch of a crawl.
+#[derive(Debug)]
+pub struct RateLimiter {
+    rates: HashMap<String, f64>,
+    default_rate: f64,
+    buckets: Mutex<HashMap<String, Bucket>>,
+}
+
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    capacity: f64,
+    rate: f64,
+    refilled_at: Instant,
+    blocked_until: Option<Instant>,
+}
+
+impl Bucket {
+    fn new(rate: f64) -> Self {
+        let capacity = rate.max(1.0);
+
+        Bucket {
+            tokens: capacity,
+            capacity,
+            rate,
+            refilled_at: Instant::now(),
+            blocked_until: None,
+        }
+    }
+
+    /// Takes a token, or returns how long to wait before trying again.
+    fn take(&mut self, now: Instant) -> Option<Duration> {
+        if let Some(blocked_until) = self.blocked_until {
+            if blocked_until > now {
+                return Some(blocked_until - now);
+            }
+            self.blocked_until = None;
+        }
+
+        let elapsed = now.duration_since(self.refilled_at).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.rate).min(self.capacity);
+        self.refilled_at = now;
+
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            None
+        } else {
+            let wait = (1.0 - self.tokens) / self.rate;
+
+            Some(Duration::try_from_secs_f64(wait).map_or(MAX_WAIT, |wait| wait.min(MAX_WAIT)))
+        }
+    }
+}
+
+impl RateLimiter {
+    /// Creates a limiter allowing `rates` requests per second for the listed
+    /// hosts and `default_rate` for all others. Hosts match their subdomains
+    /// too.
+    #[must_use]
+    pub fn new(rates: HashMap<String, f64>, default_rate: f64) -> Self {
+        RateLimiter {
+            rates,
+            default_rate,
+            buckets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Waits until a request to the URL is allowed.
+    pub async fn acquire(&self, url: &str) {
+        let host = host(url);
+
+        loop {
+            let wait = {
+                let mut buckets = self.buckets.lock().await;
+
+                buckets
+                    .entry(host.clone())
+                    .or_insert_with(|| Bucket::new(self.rate(&host)))
+                    .take(Instant::now())
+            };
+
+            match wait {
+                Some(wait) => sleep(wait).await,
+                None => return,
+            }
+        }
+    }
+
+    /// Holds back all requests to the host of the URL, as asked by a
+    /// `Retry-After` header.
+    pub async fn block(&self, url: &str, duration: Duration) {
+        let host = host(url);
+        let until = Instant::now() + duration;
+
+        let mut buckets = self.buckets.lock().await;
+
+        let bucket = buckets
+            .entry(host.clone())
+            .or_insert_with(|| Bucket::new(self.rate(&host)));
+
+        if bucket
+            .blocked_until
+            .is_none_or(|blocked_until| blocked_until < until)
+        {
+            bucket.blocked_until = Some(until);
+        }
+    }
+
+    fn rate(&self, host: &str) -> f64 {
+        self.rates
+            .iter()
+            .filter(|(limited_host, _)| {
+                host == *limited_host || host.ends_with(&format!(".{limited_host}"))
+            })
+            .max_by_key(|(limited_host, _)| limited_host.len())
+            .map_or(self.default_rate, |(_, &rate)| rate)
+            .max(MIN_RATE)
+    }
+}
+
+fn host(url: &str) -> String {
+    reqwest::Url::parse(url)
+        .ok()
+        .and_then(|url| url.host_str().map(str::to_string))
+        .unwrap_or_default()
+}
@@ -1,28 +1,349 @@
-use anyhow::{Context, Result};
-use std::fs::OpenOptions;
-use std::path::Path;
-use std::sync::atomic::{AtomicBool, Ordering};
+use anyhow::{bail, Context, Result};
+use csv::StringRecord;
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use std::fs::{self, File, OpenOptions};
+use std::hash::BuildHasher;
+use std::path::{Path, PathBuf};
 
 use crate::convert::entry::TsvEntry;
 
-static HEADER_WRITTEN: AtomicBool = AtomicBool::new(false);
+pub const ID_COLUMN: &str = "jimaku_id";
+
+/// Value written for what is not known, the same as ARFF uses.
+pub const MISSING_MARKER: &str = "?";
+
+/// Appends the entry to the TSV file like [`save_rows_to_tsv`], writing
+/// `missing_marker` for every value that is `None`.
+pub fn save_to_tsv<P: AsRef<Path>>(
+    entry: &TsvEntry,
+    file_path: P,
+    missing_marker: &str,
+) -> Result<()> {
+    let headers = tsv_header::<TsvEntry>()?;
+    let record = entry_record(entry, &headers, missing_marker)?;
+
+    let (file, is_empty) = open_appending(file_path.as_ref())?;
+
+    let mut wtr = csv::WriterBuilder::new().delimiter(b'\t').from_writer(file);
+
+    if is_empty {
+        wtr.write_record(&headers)
+            .context("Failed to write header")?;
+    }
+    wtr.write_record(&record)
+        .context("Failed to write record")?;
+    wtr.flush().context("Failed to flush")?;
+
+    Ok(())
+}
+
+/// Replaces the rows of the entries in the TSV file where they are, with a
+/// single rewrite, and appends the ones the file has none of.
+pub fn replace_saved_entries<P: AsRef<Path>>(
+    entries: &[TsvEntry],
+    file_path: P,
+    missing_marker: &str,
+) -> Result<()> {
+    let file_path = file_path.as_ref();
+
+    if !file_path.exists() {
+        for entry in entries {
+            save_to_tsv(entry, file_path, missing_marker)?;
+        }
+
+        return Ok(());
+    }
+
+    rewrite_rows(file_path, |headers, records, file_path| {
+        let id_index = column_index(headers, ID_COLUMN, file_path)?;
+        let mut new_records = entries
+            .iter()
+            .map(|entry| {
+                Ok((
+                    entry.jimaku_id,
+                    entry_record(entry, headers, missing_marker)?,
+                ))
+            })
+            .collect::<Result<HashMap<_, _>>>()?;
+        let ids: HashSet<i32> = new_records.keys().copied().collect();
+
+        let mut rows = Vec::new();
+        for old_record in records {
+            let id = parse_id(&old_record, id_index)?;
+
+            if ids.contains(&id) {
+                // the first row is replaced and any others are dropped
+                rows.extend(new_records.remove(&id));
+            } else {
+                rows.push(old_record);
+            }
+        }
+        rows.extend(
+            entries
+                .iter()
+                .filter_map(|entry| new_records.remove(&entry.jimaku_id)),
+        );
+
+        Ok(rows)
+    })
+}
+
+/// The entry as a row with the values in the order of `headers`.
+fn entry_record(
+    entry: &TsvEntry,
+    headers: &StringRecord,
+    missing_marker: &str,
+) -> Result<StringRecord> {
+    // the csv writer leaves `None` as an empty field, same as an empty
+    // string, while JSON keeps it apart as null
+    let Value::Object(values) = serde_json::to_value(entry).context("Failed to serialize")? else {
+        bail!("Failed to serialize entry as a row");
+    };
+
+    Ok(headers
+        .iter()
+        .map(|header| match values.get(header) {
+            None | Some(Value::Null) => missing_marker.to_string(),
+            Some(Value::String(value)) => value.clone(),
+            Some(value) => value.to_string(),
+        })
+        .collect())
+}
+
+/// Appends the rows to the TSV file, writing the header first if the file is
+/// new.
+pub fn save_rows_to_tsv<T: Serialize, P: AsRef<Path>>(rows: &[T], file_path: P) -> Result<()> {
+    if rows.is_empty() {
+        return Ok(());
+    }
+
+    let (file, is_empty) = open_appending(file_path.as_ref())?;
+
+    let mut wtr = csv::WriterBuilder::new()
+        .delimiter(b'\t')
+        .has_headers(is_empty)
+        .from_writer(file);
+
+    for row in rows {
+        wtr.serialize(row).context("Failed to serialize")?;
+    }
+    wtr.flush().context("Failed to flush")?;
+
+    Ok(())
+}
+
+/// Opens the file for appending, creating it and its folder, telling whether
+/// it is still empty.
+fn open_appending(file_path: &Path) -> Result<(File, bool)> {
+    if let Some(dir) = file_path.parent() {
+        fs::create_dir_all(dir).context(format!("Failed to create {}", dir.display()))?;
+    }
 
-pub fn save_to_tsv<P: AsRef<Path>>(entry: &TsvEntry, file_path: P) -> Result<()> {
     let file = OpenOptions::new()
         .append(true)
         .create(true)
         .open(file_path)
         .context("Failed to open file")?;
 
+    let is_empty = file
+        .metadata()
+        .context("Failed to get file metadata")?
+        .len()
+        == 0;
+
+    Ok((file, is_empty))
+}
+
+/// Reads every row of a TSV file written by [`save_rows_to_tsv`].
+pub fn load_rows_from_tsv<T: DeserializeOwned, P: AsRef<Path>>(file_path: P) -> Result<Vec<T>> {
+    let file_path = file_path.as_ref();
+
+    csv::ReaderBuilder::new()
+        .delimiter(b'\t')
+        .from_path(file_path)
+        .context(format!("Failed to open {}", file_path.display()))?
+        .deserialize()
+        .collect::<Result<_, _>>()
+        .context(format!("Failed to read {}", file_path.display()))
+}
+
+/// Path of a relation table crawled along with the data file, which lives
+/// next to it.
+#[must_use]
+pub fn relation_path(data_path: &Path, table: &str) -> PathBuf {
+    data_path.with_file_name(format!("{table}.tsv"))
+}
+
+/// Header a TSV file of `T` rows gets, read off a default row.
+pub fn tsv_header<T: Serialize + Default>() -> Result<StringRecord> {
     let mut wtr = csv::WriterBuilder::new()
         .delimiter(b'\t')
-        .has_headers(!HEADER_WRITTEN.load(Ordering::SeqCst))
-        .from_writer(file);
+        .from_writer(Vec::new());
+    wtr.serialize(T::default()).context("Failed to serialize")?;
+    let data = wtr.into_inner().context("Failed to flush")?;
+
+    let header = csv::ReaderBuilder::new()
+        .delimiter(b'\t')
+        .from_reader(data.as_slice())
+        .headers()
+        .context("Failed to read headers")?
+        .clone();
+
+    Ok(header)
+}
+
+/// Fails when the TSV file was written with other columns than `T` has, as
+/// appending to it would leave rows that do not match the header.
+pub fn check_saved_header<T: Serialize + Default>(file_path: &Path) -> Result<()> {
+    if !file_path.exists() || fs::metadata(file_path)?.len() == 0 {
+        return Ok(());
+    }
+
+    let (headers, _) = read_records(file_path)?;
+
+    if headers != tsv_header::<T>()? {
+        bail!(
+            "{} was written with other columns, restart the crawl to rebuild it",
+            file_path.display()
+        );
+    }
+
+    Ok(())
+}
+
+/// Returns jimaku ids of the entries already saved to the TSV file, which acts
+/// as the crawl checkpoint.
+pub fn load_saved_ids<P: AsRef<Path>>(file_path: P) -> Result<HashSet<i32>> {
+    Ok(load_saved_column(file_path, ID_COLUMN)?
+        .into_iter()
+        .map(|(id, _)| id)
+        .collect())
+}
+
+/// Returns the jimaku id and the value of `column` of every row of the TSV
+/// file, in the order of the file.
+pub fn load_saved_column<P: AsRef<Path>>(file_path: P, column: &str) -> Result<Vec<(i32, String)>> {
+    let file_path = file_path.as_ref();
+
+    if !file_path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let (headers, records) = read_records(file_path)?;
+    let id_index = column_index(&headers, ID_COLUMN, file_path)?;
+    let value_index = column_index(&headers, column, file_path)?;
+
+    records
+        .iter()
+        .map(|record| {
+            let id = parse_id(record, id_index)?;
+            let value = record.get(value_index).unwrap_or_default().to_string();
+
+            Ok((id, value))
+        })
+        .collect()
+}
+
+/// Removes the rows of the entries with the given jimaku ids from the TSV
+/// file.
+pub fn remove_saved_rows<P: AsRef<Path>, S: BuildHasher>(
+    file_path: P,
+    ids: &HashSet<i32, S>,
+) -> Result<()> {
+    rewrite_rows(file_path, |headers, records, file_path| {
+        let id_index = column_index(headers, ID_COLUMN, file_path)?;
+
+        let mut kept = Vec::new();
+        for record in records {
+            if !ids.contains(&parse_id(&record, id_index)?) {
+                kept.push(record);
+            }
+        }
+
+        Ok(kept)
+    })
+}
+
+/// Rewrites every row of the TSV file with `rewrite`, which gets the header
+/// too. The file is replaced at once, so it is never left half written.
+pub fn rewrite_rows<P, F>(file_path: P, rewrite: F) -> Result<()>
+where
+    P: AsRef<Path>,
+    F: FnOnce(&StringRecord, Vec<StringRecord>, &Path) -> Result<Vec<StringRecord>>,
+{
+    let file_path = file_path.as_ref();
 
-    wtr.serialize(entry).context("Failed to serialize")?;
+    if !file_path.exists() {
+        return Ok(());
+    }
+
+    let (headers, records) = read_records(file_path)?;
+    let records = rewrite(&headers, records, file_path)?;
+
+    let temp_path = file_path.with_extension("tsv.tmp");
+
+    let mut wtr = csv::WriterBuilder::new()
+        .delimiter(b'\t')
+        .from_path(&temp_path)
+        .context(format!("Failed to create {}", temp_path.display()))?;
+
+    wtr.write_record(&headers)
+        .context("Failed to write header")?;
+    for record in &records {
+        wtr.write_record(record).context("Failed to write record")?;
+    }
     wtr.flush().context("Failed to flush")?;
 
-    HEADER_WRITTEN.store(true, Ordering::SeqCst);
+    fs::rename(&temp_path, file_path)
+        .context(format!("Failed to replace {}", file_path.display()))?;
+
+    Ok(())
+}
+
+/// Index of the column, failing for files written before it existed.
+pub fn column_index(headers: &StringRecord, column: &str, file_path: &Path) -> Result<usize> {
+    let Some(index) = headers.iter().position(|header| header == column) else {
+        bail!(
+            "{} has no {column} column, restart the crawl to rebuild it",
+            file_path.display()
+        );
+    };
+
+    Ok(index)
+}
+
+/// Reads the jimaku id of a row.
+pub fn parse_id(record: &StringRecord, id_index: usize) -> Result<i32> {
+    record
+        .get(id_index)
+        .context("Failed to get jimaku id")?
+        .parse::<i32>()
+        .context("Failed to parse jimaku id")
+}
+
+fn read_records(file_path: &Path) -> Result<(StringRecord, Vec<StringRecord>)> {
+    let mut rdr = csv::ReaderBuilder::new()
+        .delimiter(b'\t')
+        .from_path(file_path)
+        .context(format!("Failed to open {}", file_path.display()))?;
+
+    let headers = rdr.headers().context("Failed to read headers")?.clone();
+    let records = rdr
+        .records()
+        .collect::<Result<_, _>>()
+        .context(format!("Failed to read {}", file_path.display()))?;
+
+    Ok((headers, records))
+}
+
+pub fn remove_file_if_exists<P: AsRef<Path>>(file_path: P) -> Result<()> {
+    let file_path = file_path.as_ref();
+
+    if file_path.exists() {
+        fs::remove_file(file_path).context(format!("Failed to remove {}", file_path.display()))?;
+    }
 
     Ok(())
 }
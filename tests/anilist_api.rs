@@ -0,0 +1,243 @@
+mod common;
+
+use common::{http_fetcher, StandInServer};
+use ml_parser::parse::anilist::{
+    api::{get_anilist_entries, MAX_PER_PAGE},
+    date::DatePrecision,
+    error::ParseError,
+    format::Format,
+    genre::Genre,
+    relation::RelationType,
+    source::Source,
+    status::Status,
+};
+use serde_json::{json, Value};
+
+/// A media with every field the client asks for, known to the day.
+fn full_media(id: i64) -> Value {
+    json!({
+        "id": id,
+        "format": "TV",
+        "status": "FINISHED",
+        "source": "ORIGINAL",
+        "genres": ["Action", "Sci-Fi", "Space Opera"],
+        "episodes": 26,
+        "duration": 24,
+        "startDate": { "year": 1998, "month": 4, "day": 3 },
+        "endDate": { "year": 1999, "month": 4, "day": 24 },
+        "season": "SPRING",
+        "seasonYear": 1998,
+        "countryOfOrigin": "JP",
+        "isLicensed": true,
+        "averageScore": 86,
+        "meanScore": 86,
+        "popularity": 400_000,
+        "favourites": 40_000,
+        "rankings": [
+            { "rank": 40, "type": "RATED", "allTime": true },
+            { "rank": 2, "type": "RATED", "allTime": false }
+        ],
+        "stats": {
+            "scoreDistribution": [
+                { "score": 10, "amount": 100 },
+                { "score": 100, "amount": 9000 }
+            ],
+            "statusDistribution": [
+                { "status": "COMPLETED", "amount": 300_000 },
+                { "status": "DROPPED", "amount": 5000 }
+            ]
+        },
+        "nextAiringEpisode": null,
+        "studios": { "edges": [
+            { "node": { "id": 14, "isAnimationStudio": true } },
+            { "node": { "id": 23, "isAnimationStudio": false } }
+        ] },
+        "staff": { "edges": [
+            { "role": "Original Creator", "node": { "id": 100 } },
+            { "role": "Director", "node": { "id": 101 } }
+        ] },
+        "tags": [ { "id": 1, "name": "Space", "category": "Setting-Universe", "rank": 94 } ],
+        "relations": { "edges": [
+            { "relationType": "SIDE_STORY", "node": { "id": 5, "type": "ANIME" } },
+            { "relationType": "SOME_NEW_RELATION", "node": { "id": 7, "type": "MANGA" } }
+        ] }
+    })
+}
+
+/// A media anilist knows little about yet, with values none of the enums
+/// know and dates known only in part.
+fn sparse_media(id: i64) -> Value {
+    json!({
+        "id": id,
+        "format": "AI_GENERATED",
+        "status": "ON_HOLD",
+        "source": "VIDEO_GAME_REMAKE",
+        "genres": [],
+        "episodes": null,
+        "duration": null,
+        "startDate": { "year": 2026, "month": 10, "day": null },
+        "endDate": { "year": 2027, "month": null, "day": null },
+        "season": null,
+        "seasonYear": null,
+        "countryOfOrigin": null,
+        "isLicensed": null,
+        "averageScore": null,
+        "meanScore": null,
+        "popularity": null,
+        "favourites": null,
+        "rankings": [],
+        "stats": null,
+        "nextAiringEpisode": { "episode": 3 },
+        "studios": null,
+        "staff": null,
+        "tags": [],
+        "relations": null
+    })
+}
+
+/// Answers every query with the media of the requested ids it knows, which
+/// are the ids below 1000, and the sparse one for id 5.
+async fn start_server() -> StandInServer {
+    StandInServer::start(|request| {
+        let payload: Value = serde_json::from_str(&request.body).unwrap();
+
+        let media: Vec<_> = payload["variables"]["ids"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter_map(Value::as_i64)
+            .filter(|&id| id < 1000)
+            .map(|id| {
+                if id == 5 {
+                    sparse_media(id)
+                } else {
+                    full_media(id)
+                }
+            })
+            .collect();
+
+        (
+            200,
+            json!({ "data": { "Page": { "media": media } } }).to_string(),
+        )
+    })
+    .await
+}
+
+#[tokio::test]
+async fn converts_a_full_media() {
+    let server = start_server().await;
+
+    let mut entries = get_anilist_entries(&http_fetcher(), &server.url, &[1])
+        .await
+        .unwrap();
+    let entry = entries.remove(&1).unwrap().unwrap();
+
+    assert_eq!(entry.format, Format::TvShow);
+    assert_eq!(entry.status, Status::Finished);
+    assert_eq!(entry.source, Some(Source::Original));
+    assert_eq!(
+        entry.genres,
+        vec![
+            Genre::Action,
+            Genre::SciFi,
+            Genre::Unknown("Space Opera".to_string())
+        ]
+    );
+    assert_eq!(entry.episodes_amount, Some(26));
+    assert_eq!(entry.time_required.as_deref(), Some("PT24M"));
+    assert_eq!(entry.start_date.unwrap().precision(), DatePrecision::Day);
+    assert_eq!(entry.rating_value, Some(86));
+    assert_eq!(entry.rating_count, Some(9100));
+    assert_eq!(entry.rankings.rated, Some(40));
+    assert_eq!(entry.rankings.popular, None);
+    assert_eq!(entry.score_distribution.unwrap().get(100), 9000);
+    assert_eq!(entry.status_distribution.unwrap().dropped, 5000);
+    assert_eq!(entry.production_companies, vec![14]);
+    assert_eq!(entry.producers, vec![23]);
+    assert_eq!(entry.creators, vec![100]);
+    assert_eq!(entry.tags[0].rank, 94);
+    assert_eq!(entry.relations[0].relation_type, RelationType::SideStory);
+    assert_eq!(entry.relations[0].target_type, "anime");
+
+    let request = &server.requests()[0];
+    assert_eq!(request.method, "POST");
+    assert_eq!(request.path, "/");
+    assert_eq!(request.header("content-type"), Some("application/json"));
+}
+
+#[tokio::test]
+async fn keeps_unknown_values_and_partial_dates() {
+    let server = start_server().await;
+
+    let mut entries = get_anilist_entries(&http_fetcher(), &server.url, &[5])
+        .await
+        .unwrap();
+    let entry = entries.remove(&5).unwrap().unwrap();
+
+    assert_eq!(entry.format, Format::Unknown("AI_GENERATED".to_string()));
+    assert_eq!(entry.status, Status::Unknown("ON_HOLD".to_string()));
+    assert_eq!(
+        entry.source,
+        Some(Source::Unknown("VIDEO_GAME_REMAKE".to_string()))
+    );
+    assert_eq!(entry.unknown_values().len(), 3);
+
+    let start_date = entry.start_date.unwrap();
+    assert_eq!(start_date.precision(), DatePrecision::Month);
+    assert_eq!(start_date.first_day().to_string(), "2026-10-01");
+
+    let end_date = entry.end_date.unwrap();
+    assert_eq!(end_date.precision(), DatePrecision::Year);
+    assert_eq!(end_date.first_day().to_string(), "2027-01-01");
+
+    // the next airing episode is the amount, like on the page
+    assert_eq!(entry.episodes_amount, Some(3));
+    assert_eq!(entry.score_distribution, None);
+    assert_eq!(entry.status_distribution, None);
+    assert_eq!(entry.rating_value, None);
+    assert_eq!(entry.rating_count, None);
+}
+
+#[tokio::test]
+async fn reports_ids_missing_from_the_response() {
+    let server = start_server().await;
+
+    let entries = get_anilist_entries(&http_fetcher(), &server.url, &[1, 1234])
+        .await
+        .unwrap();
+
+    assert!(entries[&1].is_ok());
+
+    let err = entries[&1234].as_ref().unwrap_err();
+    assert!(matches!(
+        err.downcast_ref::<ParseError>(),
+        Some(ParseError::NotFound(1234))
+    ));
+}
+
+#[tokio::test]
+async fn splits_ids_into_pages() {
+    let server = start_server().await;
+
+    let ids: Vec<i32> = (1..=i32::try_from(MAX_PER_PAGE).unwrap() + 10).collect();
+
+    let entries = get_anilist_entries(&http_fetcher(), &server.url, &ids)
+        .await
+        .unwrap();
+
+    assert_eq!(entries.len(), ids.len());
+    assert!(entries.values().all(Result::is_ok));
+
+    let batches: Vec<usize> = server
+        .requests()
+        .iter()
+        .map(|request| {
+            let payload: Value = serde_json::from_str(&request.body).unwrap();
+            payload["variables"]["ids"].as_array().unwrap().len()
+        })
+        .collect();
+
+    assert_eq!(batches.len(), 2);
+    assert!(batches.contains(&MAX_PER_PAGE) && batches.contains(&10));
+}
@@ -0,0 +1,46 @@
+use ml_parser::{
+    cache::PageCache,
+    fetch::{cached::CachedFetcher, scripted::ScriptedFetcher, Fetcher},
+};
+use tempfile::TempDir;
+
+const LISTING_URL: &str = "https://jimaku.test/";
+const ENTRY_URL: &str = "https://jimaku.test/entry/1";
+
+/// The listing and the entry page each change after the first request.
+fn fetcher() -> ScriptedFetcher {
+    ScriptedFetcher::new()
+        .with_body(LISTING_URL, "old listing")
+        .with_body(LISTING_URL, "new listing")
+        .with_body(ENTRY_URL, "old entry")
+        .with_body(ENTRY_URL, "new entry")
+}
+
+#[tokio::test]
+async fn fetches_listings_again_and_serves_entries_from_the_cache() {
+    let dir = TempDir::new().unwrap();
+    let fetcher = CachedFetcher::new(fetcher(), PageCache::new(dir.path()))
+        .with_fresh_urls([LISTING_URL.to_string()]);
+
+    assert_eq!(fetcher.get_body(LISTING_URL).await.unwrap(), "old listing");
+    assert_eq!(fetcher.get_body(ENTRY_URL).await.unwrap(), "old entry");
+
+    assert_eq!(fetcher.get_body(LISTING_URL).await.unwrap(), "new listing");
+    assert_eq!(fetcher.get_body(ENTRY_URL).await.unwrap(), "old entry");
+
+    // the fresh listing is stored for offline crawls
+    let cache = PageCache::new(dir.path());
+    assert_eq!(
+        cache.get_body(LISTING_URL).unwrap().as_deref(),
+        Some("new listing")
+    );
+}
+
+#[tokio::test]
+async fn fetches_everything_again_when_refreshing() {
+    let dir = TempDir::new().unwrap();
+    let fetcher = CachedFetcher::new(fetcher(), PageCache::new(dir.path())).with_refresh(true);
+
+    assert_eq!(fetcher.get_body(ENTRY_URL).await.unwrap(), "old entry");
+    assert_eq!(fetcher.get_body(ENTRY_URL).await.unwrap(), "new entry");
+}
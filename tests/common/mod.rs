@@ -0,0 +1,168 @@
+//! A stand-in HTTP server for the API clients, answering with canned
+//! responses.
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use ml_parser::{
+    fetch::http::HttpFetcher,
+    request::{rate_limit::RateLimiter, RetryPolicy},
+};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
+};
+
+/// A request the server got.
+#[derive(Debug, Clone)]
+pub struct Request {
+    pub method: String,
+    /// Path with the query, like "/api/entries/search?anime=true"
+    pub path: String,
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+}
+
+impl Request {
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(header, _)| header.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// A fetcher for the stand-in server, which gives up on the first failure.
+pub fn http_fetcher() -> HttpFetcher {
+    let policy = RetryPolicy {
+        retries: 0,
+        base_delay: Duration::ZERO,
+        max_delay: Duration::ZERO,
+    };
+
+    HttpFetcher::new(
+        policy,
+        Arc::new(RateLimiter::new(HashMap::new(), 1000.0)),
+        Duration::from_secs(2),
+        Duration::from_secs(2),
+    )
+    .unwrap()
+}
+
+type Handler = dyn Fn(&Request) -> (u16, String) + Send + Sync;
+
+/// Serves every request with the status and body the handler returns.
+pub struct StandInServer {
+    pub url: String,
+    requests: Arc<Mutex<Vec<Request>>>,
+}
+
+impl StandInServer {
+    pub async fn start<H>(handler: H) -> Self
+    where
+        H: Fn(&Request) -> (u16, String) + Send + Sync + 'static,
+    {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let handler: Arc<Handler> = Arc::new(handler);
+
+        tokio::spawn({
+            let requests = requests.clone();
+
+            async move {
+                while let Ok((stream, _)) = listener.accept().await {
+                    let handler = handler.clone();
+                    let requests = requests.clone();
+
+                    tokio::spawn(async move {
+                        let mut stream = stream;
+
+                        let Some(request) = read_request(&mut stream).await else {
+                            return;
+                        };
+
+                        let (status, body) = handler(&request);
+                        requests.lock().unwrap().push(request);
+
+                        respond(&mut stream, status, &body).await;
+                    });
+                }
+            }
+        });
+
+        StandInServer { url, requests }
+    }
+
+    /// Every request the server got, in the order they came.
+    pub fn requests(&self) -> Vec<Request> {
+        self.requests.lock().unwrap().clone()
+    }
+}
+
+async fn read_request(stream: &mut TcpStream) -> Option<Request> {
+    let mut data = Vec::new();
+    let mut buffer = [0; 4096];
+
+    let head_end = loop {
+        let read = stream.read(&mut buffer).await.ok()?;
+        if read == 0 {
+            return None;
+        }
+        data.extend_from_slice(&buffer[..read]);
+
+        if let Some(position) = data.windows(4).position(|window| window == b"\r\n\r\n") {
+            break position + 4;
+        }
+    };
+
+    let head = String::from_utf8_lossy(&data[..head_end]).to_string();
+    let mut lines = head.lines();
+
+    let mut request_line = lines.next()?.split_whitespace();
+    let method = request_line.next()?.to_string();
+    let path = request_line.next()?.to_string();
+
+    let headers: Vec<_> = lines
+        .filter_map(|line| line.split_once(':'))
+        .map(|(name, value)| (name.trim().to_string(), value.trim().to_string()))
+        .collect();
+
+    let content_length = headers
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case("content-length"))
+        .and_then(|(_, value)| value.parse::<usize>().ok())
+        .unwrap_or_default();
+
+    while data.len() < head_end + content_length {
+        let read = stream.read(&mut buffer).await.ok()?;
+        if read == 0 {
+            break;
+        }
+        data.extend_from_slice(&buffer[..read]);
+    }
+
+    Some(Request {
+        method,
+        path,
+        headers,
+        body: String::from_utf8_lossy(&data[head_end..]).to_string(),
+    })
+}
+
+/// Answers the request and closes the connection.
+async fn respond(stream: &mut TcpStream, status: u16, body: &str) {
+    let response = format!(
+        "HTTP/1.1 {status} Stand-In\r\nContent-Type: application/json\r\n\
+         Content-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    );
+
+    if stream.write_all(response.as_bytes()).await.is_ok() {
+        let _ = stream.shutdown().await;
+    }
+}
@@ -0,0 +1,58 @@
+use std::fs;
+
+use ml_parser::{
+    convert::{
+        arff::{tsv_to_arff, Options},
+        entry::TsvEntry,
+    },
+    parse::arff::ARFFData,
+    storage::{save_to_tsv, MISSING_MARKER},
+};
+use tempfile::TempDir;
+
+/// An entry crawled from a sparse anilist page: no country, source, season
+/// or license, and no files.
+fn sparse_entry(jimaku_id: i32) -> TsvEntry {
+    TsvEntry {
+        jimaku_id,
+        anilist_id: jimaku_id * 10,
+        name_romaji: format!("Anime {jimaku_id}"),
+        format: "TV".to_string(),
+        status: "FINISHED".to_string(),
+        last_modified: 1_700_000_000,
+        ..TsvEntry::default()
+    }
+}
+
+#[test]
+fn sparse_entries_convert_and_normalize() {
+    let dir = TempDir::new().unwrap();
+    let tsv_path = dir.path().join("data.tsv");
+    let arff_path = dir.path().join("data.arff");
+    let csv_path = dir.path().join("data.csv");
+
+    for jimaku_id in 1..=3 {
+        save_to_tsv(&sparse_entry(jimaku_id), &tsv_path, MISSING_MARKER).unwrap();
+    }
+
+    tsv_to_arff(&tsv_path, &arff_path, &Options::for_input(&tsv_path)).unwrap();
+
+    let arff = fs::read_to_string(&arff_path).unwrap();
+    assert!(!arff.contains("{}"), "empty nominal attribute in:\n{arff}");
+    assert!(!arff.contains("@attribute country"));
+    assert!(arff.contains("@attribute format {TV}"));
+
+    ARFFData::from_arff(&arff).to_csv_normalized(csv_path.to_str().unwrap());
+
+    let csv = fs::read_to_string(&csv_path).unwrap();
+    let mut lines = csv.lines();
+    let headers: Vec<&str> = lines.next().unwrap().split(',').collect();
+    let rows: Vec<Vec<&str>> = lines.map(|line| line.split(',').collect()).collect();
+
+    assert_eq!(rows.len(), 3);
+    assert!(headers.contains(&"format_TV"));
+    for row in &rows {
+        assert_eq!(row.len(), headers.len());
+        assert!(row.iter().all(|value| !value.contains("NaN")));
+    }
+}
@@ -0,0 +1,297 @@
+use std::{collections::HashMap, path::PathBuf, time::Duration};
+
+use ml_parser::{
+    crawl::{crawl, AnilistSource, Config, JimakuSource, Mode},
+    fetch::scripted::ScriptedFetcher,
+    storage::{load_saved_column, MISSING_MARKER},
+};
+use serde_json::json;
+use tempfile::TempDir;
+
+const JIMAKU_URL: &str = "https://jimaku.test";
+const ANILIST_API_URL: &str = "https://graphql.anilist.test";
+
+fn config(output_path: PathBuf) -> Config {
+    Config {
+        listing_urls: vec![format!("{JIMAKU_URL}/")],
+        complete_listing: true,
+        jimaku_url: JIMAKU_URL.to_string(),
+        jimaku_source: JimakuSource::Page,
+        jimaku_api_key: None,
+        anilist_url: "https://anilist.test".to_string(),
+        anilist_api_url: ANILIST_API_URL.to_string(),
+        anilist_source: AnilistSource::Api,
+        anilist_batch_size: 50,
+        concurrency: 4,
+        output_path,
+        missing_marker: MISSING_MARKER.to_string(),
+        mode: Mode::Resume,
+        max_entries: None,
+        max_failures_in_a_row: 5,
+        entry_retries: 2,
+        request_retries: 0,
+        retry_base_delay: Duration::ZERO,
+        retry_max_delay: Duration::ZERO,
+        rate_limits: HashMap::new(),
+        default_rate_limit: 1.0,
+        name_lookup: false,
+        company_names: false,
+        browser_retries: 0,
+        browser_tabs: 1,
+        browser_max_failures_in_a_row: 1,
+        page_timeout: Duration::from_secs(1),
+        request_timeout: Duration::from_secs(1),
+        connect_timeout: Duration::from_secs(1),
+        cache_dir: None,
+        offline: false,
+        download_subtitles: false,
+        open_archives: false,
+    }
+}
+
+/// Listing page with the entries, each linked to anilist id `100 + id`.
+fn listing(ids: &[i32]) -> String {
+    listing_modified(ids, 1_715_375_694)
+}
+
+/// Listing page with the entries, all changed at `last_modified`.
+fn listing_modified(ids: &[i32], last_modified: i64) -> String {
+    let entries = ids
+        .iter()
+        .map(|id| {
+            let extra = json!({
+                "name": format!("Show {id}"),
+                "flags": 1,
+                "last_modified": last_modified,
+                "anilist_id": 100 + id,
+            });
+
+            format!(
+                r#"<div class="entry" data-extra='{extra}'>
+                    <a class="table-data file-name" href="/entry/{id}">Show {id}</a>
+                </div>"#
+            )
+        })
+        .collect::<Vec<_>>()
+        .concat();
+
+    format!("<html><body>{entries}</body></html>")
+}
+
+/// Entry page with a single subtitle file.
+fn entry_page(id: i32) -> String {
+    let extra = json!({
+        "name": format!("Show {id} - 01.srt"),
+        "size": 1000 + id,
+        "last_modified": "2024-03-03T11:53:01Z",
+    });
+
+    format!(
+        r#"<html><body><div class="entry" data-extra='{extra}'>
+            <a class="table-data file-name" href="/entry/{id}/download/01.srt">01.srt</a>
+        </div></body></html>"#
+    )
+}
+
+/// One API response with the anime of every entry, as the scripted fetcher
+/// answers every batch the same.
+fn anilist_response(ids: &[i32]) -> String {
+    let media: Vec<_> = ids
+        .iter()
+        .map(|id| {
+            json!({
+                "id": 100 + id,
+                "format": "TV",
+                "status": "FINISHED",
+                "genres": ["Action"],
+                "averageScore": 70,
+            })
+        })
+        .collect();
+
+    json!({ "data": { "Page": { "media": media } } }).to_string()
+}
+
+fn fetcher(ids: &[i32]) -> ScriptedFetcher {
+    ScriptedFetcher::new()
+        .with_body(&format!("{JIMAKU_URL}/"), &listing(ids))
+        .with_body(ANILIST_API_URL, &anilist_response(ids))
+}
+
+fn saved_ids(config: &Config) -> Vec<i32> {
+    load_saved_column(&config.output_path, "jimaku_id")
+        .unwrap()
+        .into_iter()
+        .map(|(id, _)| id)
+        .collect()
+}
+
+fn entry_requests(fetcher: &ScriptedFetcher, id: i32) -> usize {
+    let url = format!("{JIMAKU_URL}/entry/{id}");
+
+    fetcher
+        .requests()
+        .iter()
+        .filter(|request| **request == url)
+        .count()
+}
+
+#[tokio::test]
+async fn saves_entries_in_listing_order() {
+    let ids = [1, 2, 3, 4, 5, 6];
+    let dir = TempDir::new().unwrap();
+    let config = config(dir.path().join("data.tsv"));
+
+    let fetcher = ids.iter().fold(fetcher(&ids), |fetcher, &id| {
+        fetcher.with_body(&format!("{JIMAKU_URL}/entry/{id}"), &entry_page(id))
+    });
+
+    crawl(&config, &fetcher).await.unwrap();
+
+    assert_eq!(saved_ids(&config), ids);
+
+    let names = load_saved_column(&config.output_path, "name_romaji").unwrap();
+    assert_eq!(names[0].1, "Show 1");
+
+    let sizes = load_saved_column(&config.output_path, "filesize_max").unwrap();
+    assert_eq!(sizes[5].1, "1006");
+
+    // the page has no popularity, so it is missing rather than zero
+    let popularity = load_saved_column(&config.output_path, "popularity").unwrap();
+    assert_eq!(popularity[0].1, MISSING_MARKER);
+}
+
+#[tokio::test]
+async fn resumes_without_fetching_saved_entries() {
+    let ids = [1, 2, 3];
+    let dir = TempDir::new().unwrap();
+    let mut config = config(dir.path().join("data.tsv"));
+    config.concurrency = 1;
+
+    let pages = |fetcher: ScriptedFetcher| {
+        ids.iter().fold(fetcher, |fetcher, &id| {
+            fetcher.with_body(&format!("{JIMAKU_URL}/entry/{id}"), &entry_page(id))
+        })
+    };
+
+    config.max_entries = Some(1);
+    crawl(&config, &pages(fetcher(&ids))).await.unwrap();
+    assert_eq!(saved_ids(&config), [1]);
+
+    config.max_entries = None;
+    let fetcher = pages(fetcher(&ids));
+    crawl(&config, &fetcher).await.unwrap();
+
+    assert_eq!(saved_ids(&config), ids);
+    assert_eq!(entry_requests(&fetcher, 1), 0);
+    assert_eq!(entry_requests(&fetcher, 2), 1);
+}
+
+#[tokio::test]
+async fn retries_transient_failures_and_skips_permanent_ones() {
+    let ids = [1, 2, 3];
+    let dir = TempDir::new().unwrap();
+    let config = config(dir.path().join("data.tsv"));
+
+    let fetcher = fetcher(&ids)
+        .with_body(&format!("{JIMAKU_URL}/entry/1"), &entry_page(1))
+        // a timeout passes when tried again
+        .with_failure(&format!("{JIMAKU_URL}/entry/2"), "timed out")
+        .with_body(&format!("{JIMAKU_URL}/entry/2"), &entry_page(2))
+        // a broken page stays broken
+        .with_body(
+            &format!("{JIMAKU_URL}/entry/3"),
+            r#"<div class="entry" data-extra='{not json'></div>"#,
+        );
+
+    crawl(&config, &fetcher).await.unwrap();
+
+    assert_eq!(saved_ids(&config), [1, 2]);
+    assert_eq!(entry_requests(&fetcher, 2), 2);
+    assert_eq!(entry_requests(&fetcher, 3), 1);
+}
+
+#[tokio::test]
+async fn aborts_after_too_many_failures_in_a_row() {
+    let ids = [1, 2, 3];
+    let dir = TempDir::new().unwrap();
+    let mut config = config(dir.path().join("data.tsv"));
+    config.max_failures_in_a_row = 2;
+
+    let fetcher = ids.iter().fold(fetcher(&ids), |fetcher, &id| {
+        fetcher.with_failure(&format!("{JIMAKU_URL}/entry/{id}"), "timed out")
+    });
+
+    assert!(crawl(&config, &fetcher).await.is_err());
+    assert!(saved_ids(&config).is_empty());
+}
+
+#[tokio::test]
+async fn refreshes_changed_entries_in_place() {
+    let dir = TempDir::new().unwrap();
+    let mut config = config(dir.path().join("data.tsv"));
+
+    let fetcher = [1, 2, 3].iter().fold(fetcher(&[1, 2, 3]), |fetcher, &id| {
+        fetcher.with_body(&format!("{JIMAKU_URL}/entry/{id}"), &entry_page(id))
+    });
+    crawl(&config, &fetcher).await.unwrap();
+
+    // every entry left changed, entry 3 is gone, and entry 1 fails for good
+    config.mode = Mode::Incremental;
+    let fetcher = ScriptedFetcher::new()
+        .with_body(
+            &format!("{JIMAKU_URL}/"),
+            &listing_modified(&[1, 2], 1_800_000_000),
+        )
+        .with_body(ANILIST_API_URL, &anilist_response(&[1, 2]))
+        .with_body(
+            &format!("{JIMAKU_URL}/entry/1"),
+            "<div class=\"entry\"></div>",
+        )
+        .with_body(&format!("{JIMAKU_URL}/entry/2"), &entry_page(2));
+    crawl(&config, &fetcher).await.unwrap();
+
+    assert_eq!(saved_ids(&config), [1, 2, 3]);
+
+    let modified = load_saved_column(&config.output_path, "last_modified").unwrap();
+    assert_eq!(modified[0].1, "1715375694");
+    assert_eq!(modified[1].1, "1800000000");
+
+    let removed = load_saved_column(&config.output_path, "removed").unwrap();
+    let removed: Vec<_> = removed.iter().map(|(_, value)| value.as_str()).collect();
+    assert_eq!(removed, ["false", "false", "true"]);
+}
+
+#[tokio::test]
+async fn keeps_entries_outside_a_partial_listing() {
+    let dir = TempDir::new().unwrap();
+    let mut config = config(dir.path().join("data.tsv"));
+
+    let fetcher = [1, 2, 3].iter().fold(fetcher(&[1, 2, 3]), |fetcher, &id| {
+        fetcher.with_body(&format!("{JIMAKU_URL}/entry/{id}"), &entry_page(id))
+    });
+    crawl(&config, &fetcher).await.unwrap();
+
+    // only entries 1 and 2 are listed, and entry 1 has no files any more
+    config.mode = Mode::Incremental;
+    config.complete_listing = false;
+    let fetcher = ScriptedFetcher::new()
+        .with_body(
+            &format!("{JIMAKU_URL}/"),
+            &listing_modified(&[1, 2], 1_800_000_000),
+        )
+        .with_body(ANILIST_API_URL, &anilist_response(&[1, 2]))
+        .with_body(&format!("{JIMAKU_URL}/entry/1"), "<html></html>")
+        .with_body(&format!("{JIMAKU_URL}/entry/2"), &entry_page(2));
+    crawl(&config, &fetcher).await.unwrap();
+
+    assert_eq!(saved_ids(&config), [1, 2, 3]);
+
+    let modified = load_saved_column(&config.output_path, "last_modified").unwrap();
+    assert_eq!(modified[0].1, "1715375694");
+    assert_eq!(modified[1].1, "1800000000");
+
+    let removed = load_saved_column(&config.output_path, "removed").unwrap();
+    let removed: Vec<_> = removed.iter().map(|(_, value)| value.as_str()).collect();
+    assert_eq!(removed, ["false", "false", "false"]);
+}
@@ -0,0 +1,39 @@
+use std::{collections::HashMap, sync::Arc, time::Duration};
+
+use ml_parser::{
+    fetch::{http::HttpFetcher, Fetcher},
+    request::{error::FetchError, rate_limit::RateLimiter, RetryPolicy},
+};
+use tokio::net::TcpListener;
+
+#[tokio::test]
+async fn times_out_when_the_server_never_answers() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}/", listener.local_addr().unwrap());
+
+    // accepts the connection and keeps it open without a response
+    tokio::spawn(async move {
+        let (_stream, _) = listener.accept().await.unwrap();
+        std::future::pending::<()>().await;
+    });
+
+    let policy = RetryPolicy {
+        retries: 0,
+        base_delay: Duration::ZERO,
+        max_delay: Duration::ZERO,
+    };
+    let fetcher = HttpFetcher::new(
+        policy,
+        Arc::new(RateLimiter::new(HashMap::new(), 1000.0)),
+        Duration::from_millis(200),
+        Duration::from_millis(200),
+    )
+    .unwrap();
+
+    let err = fetcher.get_body(&url).await.unwrap_err();
+
+    assert!(matches!(
+        err.downcast_ref::<FetchError>(),
+        Some(FetchError::Timeout { .. })
+    ));
+}
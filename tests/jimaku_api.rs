@@ -0,0 +1,135 @@
+mod common;
+
+use common::{http_fetcher, StandInServer};
+use ml_parser::{
+    convert::flags::EntryFlags,
+    parse::jimaku::api::{get_entries, get_files, search_path},
+};
+
+const API_KEY: &str = "test-key";
+
+/// Entries in the shape of the API docs, with the flags as booleans and the
+/// time as RFC 3339.
+const ENTRIES: &str = r#"[
+    {
+        "id": 1,
+        "name": "Cowboy Bebop",
+        "flags": {"anime": true, "unverified": false, "external": false, "movie": false, "adult": false},
+        "last_modified": "2024-05-10T21:14:54.123456Z",
+        "creator_id": 3,
+        "anilist_id": 1,
+        "tmdb_id": null,
+        "english_name": "Cowboy Bebop",
+        "japanese_name": "カウボーイビバップ",
+        "notes": null
+    },
+    {
+        "id": 2,
+        "name": "Tengoku Daimakyou",
+        "flags": {"anime": true, "unverified": true, "external": true, "movie": true, "adult": false},
+        "last_modified": "2024-08-18T23:10:16+00:00",
+        "anilist_id": null,
+        "tmdb_id": "tv:12345",
+        "english_name": null,
+        "japanese_name": null
+    }
+]"#;
+
+const FILES: &str = r#"[
+    {
+        "url": "https://jimaku.cc/entry/1/download/Cowboy%20Bebop%20-%2001.srt",
+        "name": "Cowboy Bebop - 01.srt",
+        "size": 31337,
+        "last_modified": "2024-03-03T11:53:01Z"
+    },
+    {
+        "url": "https://jimaku.cc/entry/1/download/Cowboy%20Bebop%20-%2002.ass",
+        "name": "Cowboy Bebop - 02.ass",
+        "size": 42,
+        "last_modified": "2024-03-04T11:53:01Z"
+    }
+]"#;
+
+async fn start_server() -> StandInServer {
+    StandInServer::start(|request| {
+        if request.header("authorization") != Some(API_KEY) {
+            return (401, r#"{"error": "Unauthorized"}"#.to_string());
+        }
+
+        match request.path.as_str() {
+            "/api/entries/search?anime=true" => (200, ENTRIES.to_string()),
+            "/api/entries/1/files" => (200, FILES.to_string()),
+            _ => (404, r#"{"error": "Not found"}"#.to_string()),
+        }
+    })
+    .await
+}
+
+#[tokio::test]
+async fn reads_entries_in_the_documented_shape() {
+    let server = start_server().await;
+    let url = format!("{}{}", server.url, search_path(true));
+
+    let entries = get_entries(&http_fetcher(), &url, Some(API_KEY))
+        .await
+        .unwrap();
+
+    assert_eq!(entries.len(), 2);
+
+    let bebop = &entries[0];
+    assert_eq!(bebop.id, 1);
+    assert_eq!(bebop.name, "Cowboy Bebop");
+    assert_eq!(bebop.last_modified, 1_715_375_694);
+    assert_eq!(bebop.anilist_id, Some(1));
+    assert_eq!(bebop.japanese_name.as_deref(), Some("カウボーイビバップ"));
+
+    let flags = EntryFlags::new(bebop.flags);
+    assert!(flags.is_anime());
+    assert!(!flags.is_unverified() && !flags.is_external() && !flags.is_movie());
+
+    let other = &entries[1];
+    assert_eq!(other.last_modified, 1_724_022_616);
+    assert_eq!(other.anilist_id, None);
+
+    let flags = EntryFlags::new(other.flags);
+    assert!(flags.is_unverified() && flags.is_external() && flags.is_movie());
+    assert!(!flags.is_adult());
+
+    let requests = server.requests();
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0].method, "GET");
+    assert_eq!(requests[0].header("accept"), Some("application/json"));
+    assert!(requests[0].body.is_empty());
+}
+
+#[tokio::test]
+async fn reads_files_with_their_download_links() {
+    let server = start_server().await;
+
+    let files = get_files(&http_fetcher(), &server.url, 1, Some(API_KEY))
+        .await
+        .unwrap();
+
+    assert_eq!(files.len(), 2);
+    assert_eq!(files[0].name, "Cowboy Bebop - 01.srt");
+    assert_eq!(files[0].size, 31337);
+    assert_eq!(files[0].last_modified, "2024-03-03T11:53:01Z");
+    assert_eq!(
+        files[0].url.as_deref(),
+        Some("https://jimaku.cc/entry/1/download/Cowboy%20Bebop%20-%2001.srt")
+    );
+    assert!(files[1].cues.is_none() && files[1].members.is_none());
+
+    assert_eq!(server.requests()[0].path, "/api/entries/1/files");
+}
+
+#[tokio::test]
+async fn fails_without_the_api_key() {
+    let server = start_server().await;
+    let url = format!("{}{}", server.url, search_path(true));
+
+    let result = get_entries(&http_fetcher(), &url, None).await;
+
+    assert!(result.is_err());
+    assert_eq!(server.requests()[0].header("authorization"), None);
+}